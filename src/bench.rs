@@ -0,0 +1,109 @@
+use crate::error::SolveError;
+use crate::{Part, Solve};
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// Min, median and max of a set of timing samples.
+#[derive(Debug, Clone, Copy)]
+pub struct Timings {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Timings {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        Timings {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+pub struct BenchResult {
+    pub day: usize,
+    pub part: Part,
+    pub parse: Timings,
+    /// Time spent solving the already parsed input.
+    pub solve: Timings,
+}
+
+/// Times parsing and solving one part of a day over `iterations` runs.
+pub fn bench_part(
+    day: usize,
+    solution: &dyn Solve,
+    part: Part,
+    input: &str,
+    iterations: usize,
+) -> Result<BenchResult, SolveError> {
+    let iterations = iterations.max(1);
+    let mut parse_samples = Vec::with_capacity(iterations);
+    let mut solve_samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let (parse_time, solve_time) = solution.time(part, input)?;
+        parse_samples.push(parse_time);
+        solve_samples.push(solve_time);
+    }
+    Ok(BenchResult {
+        day,
+        part,
+        parse: Timings::from_samples(parse_samples),
+        solve: Timings::from_samples(solve_samples),
+    })
+}
+
+/// Benchmarks both parts of every day, printing a summary to stdout and writing
+/// tab-separated results to `out`. Days whose input is missing or fails are reported and skipped.
+pub fn run(days: &[&dyn Solve], iterations: usize, out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "day\tpart\tphase\tmin_ns\tmedian_ns\tmax_ns")?;
+    for (i, solution) in days.iter().enumerate() {
+        let day = i + 1;
+        let input = match crate::input::load(day) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Day {day:02}: skipped, {e}");
+                continue;
+            }
+        };
+        for part in Part::BOTH {
+            match bench_part(day, *solution, part, &input, iterations) {
+                Ok(result) => {
+                    print_result(&result);
+                    write_result(out, &result)?;
+                }
+                Err(e) => eprintln!("Day {day:02} part {part}: failed, {e}"),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_result(result: &BenchResult) {
+    let BenchResult {
+        day,
+        part,
+        parse,
+        solve,
+    } = result;
+    println!(
+        "Day {day:02} part {part}: parse {:?} / {:?} / {:?}, solve {:?} / {:?} / {:?} (min / median / max)",
+        parse.min, parse.median, parse.max, solve.min, solve.median, solve.max
+    );
+}
+
+fn write_result(out: &mut impl Write, result: &BenchResult) -> io::Result<()> {
+    for (phase, t) in [("parse", result.parse), ("solve", result.solve)] {
+        writeln!(
+            out,
+            "{}\t{}\t{phase}\t{}\t{}\t{}",
+            result.day,
+            result.part,
+            t.min.as_nanos(),
+            t.median.as_nanos(),
+            t.max.as_nanos()
+        )?;
+    }
+    Ok(())
+}
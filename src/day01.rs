@@ -1,34 +1,148 @@
-use aoc::parse_lines_with;
-use std::iter::repeat_n;
+use crate::error::{ParseError, SolveError, parse_num, try_parse_lines};
+use std::num::NonZeroU64;
 
 pub struct Solution;
 
-impl aoc::Solution for Solution {
-    fn solve_1(&self, input: String) -> String {
-        solve(parse_lines_with(&input, parse_line))
+impl crate::Day for Solution {
+    type Input = Vec<i64>;
+
+    fn parse(&self, input: &str) -> Result<Vec<i64>, SolveError> {
+        Ok(try_parse_lines(input, parse_line)?)
+    }
+
+    fn solve_1(&self, rotations: Vec<i64>) -> Result<String, SolveError> {
+        Ok(Dial::default().count_stops(rotations).to_string())
     }
 
-    fn solve_2(&self, input: String) -> String {
-        solve(
-            parse_lines_with(&input, parse_line)
-                .flat_map(|n| repeat_n(n.signum(), n.unsigned_abs() as usize)),
-        )
+    fn solve_2(&self, rotations: Vec<i64>) -> Result<String, SolveError> {
+        Ok(Dial::default().count_passes(rotations)?.to_string())
+    }
+}
+
+/// Parses a rotation like `L68` or `R48` into a signed click count.
+pub fn parse_line(line: &str) -> Result<i64, ParseError> {
+    let sign = match line.chars().next() {
+        Some('L') => -1,
+        Some('R') => 1,
+        Some(c) => {
+            return Err(ParseError::at_column(
+                1,
+                format!("expected 'L' or 'R', found '{c}'"),
+            ));
+        }
+        None => return Err(ParseError::at_column(1, "empty rotation")),
+    };
+    // a sign of its own would turn the rotation around
+    let clicks = &line[1..];
+    if let Some((i, c)) = clicks.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        return Err(ParseError::at_column(
+            i + 2,
+            format!("expected a digit, found '{c}'"),
+        ));
     }
+    Ok(sign * parse_num::<i64>(line, clicks)?)
 }
 
-fn parse_line(line: &str) -> i32 {
-    line.replace('L', "-")
-        .replace('R', "")
-        .parse::<i32>()
-        .unwrap()
+/// A dial of `size` positions numbered from 0, pointing at `start` before any rotation. Landing
+/// on or turning past any of the `marks` counts as a hit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dial {
+    size: NonZeroU64,
+    start: u64,
+    marks: Vec<u64>,
 }
 
-fn solve(iter: impl Iterator<Item = i32>) -> String {
-    iter.scan(50, |acc, n| {
-        *acc += n;
-        Some(*acc)
-    })
-    .filter(|it| it % 100 == 0)
-    .count()
-    .to_string()
+impl Default for Dial {
+    /// The puzzle's dial: 100 positions, starting at 50, with hits counted on 0.
+    fn default() -> Self {
+        const SIZE: NonZeroU64 = NonZeroU64::new(100).unwrap();
+        Dial::new(SIZE, 50, [0])
+    }
+}
+
+impl Dial {
+    /// Creates a dial, wrapping the start and marks onto it and dropping duplicate marks.
+    pub fn new(size: NonZeroU64, start: u64, marks: impl IntoIterator<Item = u64>) -> Self {
+        let mut marks: Vec<u64> = marks.into_iter().map(|m| m % size).collect();
+        marks.sort_unstable();
+        marks.dedup();
+        Dial {
+            size,
+            start: start % size,
+            marks,
+        }
+    }
+
+    #[must_use]
+    pub fn size(&self) -> NonZeroU64 {
+        self.size
+    }
+
+    #[must_use]
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// The marks, in order and without duplicates.
+    #[must_use]
+    pub fn marks(&self) -> &[u64] {
+        &self.marks
+    }
+
+    /// Returns where the dial points before any rotation and after each one.
+    pub fn trace(&self, rotations: impl IntoIterator<Item = i64>) -> Vec<u64> {
+        let mut trace = vec![self.start];
+        let mut position = self.start;
+        for n in rotations {
+            position = self.turn(position, n);
+            trace.push(position);
+        }
+        trace
+    }
+
+    /// Counts the rotations that leave the dial on a mark.
+    pub fn count_stops(&self, rotations: impl IntoIterator<Item = i64>) -> usize {
+        self.trace(rotations)[1..]
+            .iter()
+            .filter(|p| self.marks.binary_search(p).is_ok())
+            .count()
+    }
+
+    /// Counts every click that leaves the dial on a mark, including those in the middle of a
+    /// rotation. Each rotation is counted arithmetically, so the size of the rotations doesn't
+    /// matter, but the total has to fit in a `u64`.
+    pub fn count_passes(
+        &self,
+        rotations: impl IntoIterator<Item = i64>,
+    ) -> Result<u64, SolveError> {
+        let size = u128::from(self.size.get());
+        let mut position = self.start;
+        let mut passes = 0u64;
+        for n in rotations {
+            for &mark in &self.marks {
+                // how far past the mark the dial is, measured in the direction it's about to turn
+                let past_mark = (u128::from(position) + size - u128::from(mark)) % size;
+                let past_mark = if n < 0 {
+                    (size - past_mark) % size
+                } else {
+                    past_mark
+                };
+                let hits = (past_mark + u128::from(n.unsigned_abs())) / size;
+                passes = u64::try_from(hits)
+                    .ok()
+                    .and_then(|hits| passes.checked_add(hits))
+                    .ok_or(SolveError::Unsolvable(
+                        "count of passes overflows u64".into(),
+                    ))?;
+            }
+            position = self.turn(position, n);
+        }
+        Ok(passes)
+    }
+
+    fn turn(&self, position: u64, n: i64) -> u64 {
+        let size = i128::from(self.size.get());
+        let offset = i128::from(n).rem_euclid(size);
+        u64::try_from((i128::from(position) + offset) % size).expect("positions fit on the dial")
+    }
 }
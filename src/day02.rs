@@ -1,99 +1,167 @@
-use std::collections::HashSet;
-use std::iter::once;
-use std::num::IntErrorKind;
+use crate::error::{ParseError, SolveError, column_of, parse_num};
 
 pub struct Solution;
 
-impl aoc::Solution for Solution {
-    fn solve_1(&self, input: String) -> String {
-        solve(&input, &once(2))
+impl crate::Day for Solution {
+    type Input = Vec<(u128, u128)>;
+
+    fn parse(&self, input: &str) -> Result<Vec<(u128, u128)>, SolveError> {
+        Ok(parse_ranges(input)?)
+    }
+
+    fn solve_1(&self, ranges: Vec<(u128, u128)>) -> Result<String, SolveError> {
+        Ok(solve(&ranges, 2)?.to_string())
     }
 
-    fn solve_2(&self, input: String) -> String {
-        solve(&input, &(2..))
+    fn solve_2(&self, ranges: Vec<(u128, u128)>) -> Result<String, SolveError> {
+        Ok(solve(&ranges, u32::MAX)?.to_string())
     }
 }
 
-fn solve(input: &str, chunks_counts: &(impl Iterator<Item = usize> + Clone)) -> String {
-    let ranges = parse_ranges(input);
+/// Sums every ID in the input ranges that is made of a block repeated between 2 and
+/// `max_chunks` times.
+pub fn solve(ranges: &[(u128, u128)], max_chunks: u32) -> Result<u128, SolveError> {
     ranges
         .iter()
-        .flat_map(|r| generate_invalid(r.0, r.1, chunks_counts.clone()))
-        .sum::<u64>()
-        .to_string()
+        .try_fold(0u128, |total, &(start, end)| {
+            total.checked_add(sum_invalid(start, end, max_chunks)?)
+        })
+        .ok_or(SolveError::Unsolvable(
+            "sum of invalid IDs overflows u128".into(),
+        ))
 }
 
-fn generate_invalid(
-    start: u64,
-    end: u64,
-    chunks_counts: impl Iterator<Item = usize>,
-) -> HashSet<u64> {
-    let mut invalids = HashSet::new();
-    let start_chars = start.to_string().chars().collect::<Vec<_>>();
-    let end_chars = end.to_string().chars().collect::<Vec<_>>();
-
-    for chunks in chunks_counts {
-        if chunks > end_chars.len() {
-            break;
-        }
-        log::info!("checking range {start}-{end} for invalids with {chunks} chunks");
-        let mut chunk = start_chars[0..(start_chars.len() / chunks)].to_vec();
-        if chunk.is_empty() {
-            chunk.push('1');
+/// Generates the IDs in `start..=end` made of a block repeated between 2 and `max_chunks` times,
+/// without repeats.
+pub fn generate_invalid(start: u128, end: u128, max_chunks: u32) -> impl Iterator<Item = u128> {
+    lengths(start, end).flat_map(move |(len, lo, hi)| {
+        chunk_counts(len, max_chunks).flat_map(move |chunks| {
+            let multiplier = repunit(len, len / chunks);
+            (lo.div_ceil(multiplier)..=hi / multiplier)
+                .map(move |block| block * multiplier)
+                // only produced for the most chunks it splits into, so it comes out once
+                .filter(move |&id| {
+                    !chunk_counts(len, max_chunks)
+                        .any(|more| more > chunks && id.is_multiple_of(repunit(len, len / more)))
+                })
+        })
+    })
+}
+
+/// Checks whether `id` is made of a block repeated between 2 and `max_chunks` times, returning
+/// the shortest such block and how many times it repeats.
+#[must_use]
+pub fn is_invalid(id: u128, max_chunks: u32) -> Option<(u128, u32)> {
+    let len = digits(id);
+    chunk_counts(len, max_chunks)
+        .filter(|&chunks| id.is_multiple_of(repunit(len, len / chunks)))
+        .last()
+        .map(|chunks| (id / repunit(len, len / chunks), chunks))
+}
+
+/// Sums the IDs that [`generate_invalid`] would produce without enumerating them, or `None` if
+/// the sum overflows.
+#[must_use]
+pub fn sum_invalid(start: u128, end: u128, max_chunks: u32) -> Option<u128> {
+    combine_repeating(start, end, max_chunks, |lo, hi, multiplier| {
+        let (first, last) = (lo.div_ceil(multiplier), hi / multiplier);
+        if first > last {
+            return Some(0);
         }
-        loop {
-            let chunk_len = chunk.len();
-            let candidate = match chunk
+        let (count, ends) = (last - first + 1, first + last);
+        let chunk_sum = if count.is_multiple_of(2) {
+            (count / 2).checked_mul(ends)?
+        } else {
+            count.checked_mul(ends / 2)?
+        };
+        chunk_sum.checked_mul(multiplier)
+    })
+}
+
+/// Counts the IDs that [`generate_invalid`] would produce without enumerating them.
+///
+/// # Panics
+///
+/// Never: there are fewer invalid IDs than IDs, so the count fits in a `u128`.
+#[must_use]
+pub fn count_invalid(start: u128, end: u128, max_chunks: u32) -> u128 {
+    combine_repeating(start, end, max_chunks, |lo, hi, multiplier| {
+        Some((hi / multiplier + 1).saturating_sub(lo.div_ceil(multiplier)))
+    })
+    .expect("there are fewer invalid IDs than IDs")
+}
+
+/// Totals `measure` over the invalid IDs in `start..=end`. For each digit length, `measure` is
+/// given `lo..=hi` and the multiplier for one block length, and answers for every ID in range
+/// made of that block repeated. IDs that repeat blocks of several lengths are then counted once
+/// by inclusion-exclusion over the divisors of the length.
+fn combine_repeating(
+    start: u128,
+    end: u128,
+    max_chunks: u32,
+    measure: impl Fn(u128, u128, u128) -> Option<u128>,
+) -> Option<u128> {
+    lengths(start, end).try_fold(0u128, |mut total, (len, lo, hi)| {
+        // totals for the IDs whose shortest repeating block has each length dividing `len`
+        let mut exact: Vec<(u32, u128)> = vec![];
+        for block in (1..len).filter(|&b| len.is_multiple_of(b)) {
+            let shorter: u128 = exact
                 .iter()
-                .cycle()
-                .take(chunks * chunk_len)
-                .collect::<String>()
-                .parse::<u64>()
+                .filter(|&&(b, _)| block.is_multiple_of(b))
+                .map(|(_, sum)| sum)
+                .sum();
+            let sum = measure(lo, hi, repunit(len, block))? - shorter;
+            exact.push((block, sum));
+            // an ID with this shortest block splits into any factor of `chunks`, so it counts if
+            // the smallest one is allowed
+            let chunks = len / block;
+            if (2..=chunks)
+                .find(|&k| chunks.is_multiple_of(k))
+                .is_some_and(|k| k <= max_chunks)
             {
-                Ok(n) => n,
-                Err(e) if matches!(e.kind(), IntErrorKind::PosOverflow) => break,
-                Err(e) => panic!("Unexpected parse error for candidate ID: {e:?}"),
-            };
-
-            if candidate > end {
-                break;
+                total = total.checked_add(sum)?;
             }
+        }
+        Some(total)
+    })
+}
 
-            log::debug!("Checking candidate invalid ID: {candidate}");
+/// Splits `start..=end` by digit count into `(len, lo, hi)`.
+fn lengths(start: u128, end: u128) -> impl Iterator<Item = (u32, u128, u128)> {
+    (digits(start)..=digits(end)).map(move |len| {
+        let lo = 10u128.pow(len - 1);
+        let hi = 10u128.checked_pow(len).map_or(u128::MAX, |p| p - 1);
+        (len, lo.max(start), hi.min(end))
+    })
+}
 
-            increment_chunk(&mut chunk);
+/// The numbers of chunks between 2 and `max_chunks` that a `len`-digit ID splits evenly into.
+fn chunk_counts(len: u32, max_chunks: u32) -> impl Iterator<Item = u32> {
+    (2..=len.min(max_chunks)).filter(move |&k| len.is_multiple_of(k))
+}
 
-            if candidate < start {
-                continue;
-            }
-            invalids.insert(candidate);
-            log::debug!("Found invalid ID: {candidate}");
-        }
-    }
-    invalids
+/// The multiplier that repeats a `block`-digit chunk out to `len` digits, e.g. 1001001 for a
+/// 3-digit chunk in a 9-digit ID.
+fn repunit(len: u32, block: u32) -> u128 {
+    (0..len / block).fold(0, |m, _| m * 10u128.pow(block) + 1)
 }
 
-fn increment_chunk(chunk: &mut Vec<char>) {
-    for i in (0..chunk.len()).rev() {
-        if chunk[i] == '9' {
-            chunk[i] = '0';
-        } else {
-            chunk[i] = ((chunk[i] as u8) + 1) as char;
-            return;
-        }
-    }
-    chunk.insert(0, '1');
+fn digits(n: u128) -> u32 {
+    n.checked_ilog10().map_or(1, |d| d + 1)
 }
 
-fn parse_ranges(input: &str) -> Vec<(u64, u64)> {
-    input
-        .trim()
-        .split(',')
+/// Parses the comma-separated list of `start-end` ranges.
+pub fn parse_ranges(input: &str) -> Result<Vec<(u128, u128)>, ParseError> {
+    let line = input.trim();
+    line.split(',')
         .map(|part| {
-            let mut bounds = part.split('-').map(|s| s.parse().unwrap());
-            let start = bounds.next().unwrap();
-            let end = bounds.next().unwrap();
-            (start, end)
+            let (start, end) = part.split_once('-').ok_or_else(|| {
+                ParseError::at_column(
+                    column_of(line, part),
+                    format!("expected 'start-end', found '{part}'"),
+                )
+            })?;
+            Ok((parse_num(line, start)?, parse_num(line, end)?))
         })
         .collect()
 }
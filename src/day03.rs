@@ -1,47 +1,88 @@
-use aoc::parse_lines_with;
+use crate::error::{ParseError, SolveError, try_parse_lines};
+use num_bigint::BigUint;
 
 pub struct Solution;
 
-impl aoc::Solution for Solution {
-    fn solve_1(&self, input: String) -> String {
-        solve(&input, 2)
+impl crate::Day for Solution {
+    type Input = Vec<Vec<u64>>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Vec<u64>>, SolveError> {
+        Ok(try_parse_lines(input, parse_line)?)
+    }
+
+    fn solve_1(&self, banks: Vec<Vec<u64>>) -> Result<String, SolveError> {
+        Ok(solve(&banks, 2)?.to_string())
     }
 
-    fn solve_2(&self, input: String) -> String {
-        solve(&input, 12)
+    fn solve_2(&self, banks: Vec<Vec<u64>>) -> Result<String, SolveError> {
+        Ok(solve(&banks, 12)?.to_string())
     }
 }
 
-fn solve(input: &str, num_digits: usize) -> String {
-    parse_lines_with(input, parse_line)
-        .map(|it| solve_bank(&it, num_digits))
-        .sum::<u64>()
-        .to_string()
+/// Sums the maximum joltage of every bank in the input when turning on `num_digits` batteries.
+pub fn solve(banks: &[Vec<u64>], num_digits: usize) -> Result<BigUint, SolveError> {
+    if let Some((i, bank)) = banks
+        .iter()
+        .enumerate()
+        .find(|(_, bank)| bank.len() < num_digits)
+    {
+        return Err(SolveError::Unsolvable(
+            format!(
+                "bank on line {} has {} batteries, need {num_digits}",
+                i + 1,
+                bank.len()
+            )
+            .into(),
+        ));
+    }
+    Ok(banks
+        .iter()
+        .map(|it| solve_bank(it, num_digits).joltage)
+        .sum::<BigUint>())
 }
 
-fn parse_line(line: &str) -> Vec<u64> {
-    line.chars()
-        .map(|c| c.to_digit(10).unwrap().into())
+/// Parses a bank of batteries into their joltage digits.
+pub fn parse_line(line: &str) -> Result<Vec<u64>, ParseError> {
+    line.char_indices()
+        .map(|(i, c)| {
+            c.to_digit(10)
+                .map(u64::from)
+                .ok_or_else(|| ParseError::at_column(i + 1, format!("invalid joltage '{c}'")))
+        })
         .collect()
 }
 
-#[allow(clippy::cast_possible_truncation)]
-fn solve_bank(bank: &[u64], num_digits: usize) -> u64 {
-    if num_digits == 1 {
-        return *bank.iter().max().unwrap();
-    }
+/// The batteries turned on in a bank and the joltage they produce.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    /// Positions of the chosen batteries in the bank, in order.
+    pub indices: Vec<usize>,
+    pub joltage: BigUint,
+}
 
-    let len = bank.len();
-    // find index of first largest value in bank[0..=len-num_digits]
-    let mut first_index = 0;
-    let mut first_value = bank[0];
-    for (i, &v) in bank[0..=len - num_digits].iter().enumerate().skip(1) {
-        if v > first_value {
-            first_value = v;
-            first_index = i;
+/// Finds the largest number formed by picking `num_digits` batteries from the bank in order, in a
+/// single pass.
+///
+/// # Panics
+///
+/// If a joltage in the bank isn't a single digit, which [`parse_line`] never produces.
+#[must_use]
+pub fn solve_bank(bank: &[u64], num_digits: usize) -> Selection {
+    let mut skips_left = bank.len().saturating_sub(num_digits);
+    let mut indices: Vec<usize> = Vec::with_capacity(bank.len());
+    for (i, &v) in bank.iter().enumerate() {
+        // a bigger battery later on beats a smaller one before it, while we can still skip some
+        while skips_left > 0 && indices.last().is_some_and(|&last| bank[last] < v) {
+            indices.pop();
+            skips_left -= 1;
         }
+        indices.push(i);
     }
-
-    let suffix_value = solve_bank(&bank[first_index + 1..], num_digits - 1);
-    first_value * 10u64.pow((num_digits - 1) as u32) + suffix_value
+    indices.truncate(num_digits);
+    let digits: Vec<u8> = indices
+        .iter()
+        .map(|&i| u8::try_from(bank[i]).expect("joltages are single digits"))
+        .collect();
+    let joltage = BigUint::from_radix_be(&digits, 10).expect("joltages are single digits");
+    Selection { indices, joltage }
 }
@@ -1,62 +1,169 @@
+use crate::erosion::{ErosionRule, Neighbourhood, Wave};
+use crate::error::{SolveError, parse_grid};
+use crate::image::write_scaled_rows;
 use aoc::{Grid, Point};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::iter::once;
 
 pub struct Solution;
 
-impl aoc::Solution for Solution {
-    fn solve_1(&self, input: String) -> String {
-        let grid: Grid<Tile> = input.parse().unwrap();
+impl crate::Day for Solution {
+    type Input = Grid<Tile>;
 
-        find_accessible(&grid).len().to_string()
+    fn parse(&self, input: &str) -> Result<Grid<Tile>, SolveError> {
+        Ok(parse_grid(input, Tile::parse)?)
     }
 
-    fn solve_2(&self, input: String) -> String {
-        let mut grid: Grid<Tile> = input.parse().unwrap();
-
-        let mut count = 0;
-        loop {
-            let accessible = find_accessible(&grid);
-            if accessible.is_empty() {
-                break;
-            }
-
-            count += accessible.len();
+    fn solve_1(&self, grid: Grid<Tile>) -> Result<String, SolveError> {
+        Ok(find_accessible(&grid).len().to_string())
+    }
 
-            for p in accessible {
-                grid.set(p, Tile::Empty);
-            }
-        }
-        count.to_string()
+    fn solve_2(&self, mut grid: Grid<Tile>) -> Result<String, SolveError> {
+        Ok(remove_all_accessible(&mut grid).to_string())
     }
 }
 
-enum Tile {
+#[derive(Clone, Copy)]
+pub enum Tile {
     Empty,
     Roll,
 }
 
-impl From<char> for Tile {
-    fn from(c: char) -> Self {
+impl Tile {
+    #[must_use]
+    pub fn parse(c: char) -> Option<Self> {
         match c {
-            '.' => Tile::Empty,
-            '@' => Tile::Roll,
-            _ => panic!("Invalid tile character: {c}"),
+            '.' => Some(Tile::Empty),
+            '@' => Some(Tile::Roll),
+            _ => None,
         }
     }
 }
 
-fn find_accessible(grid: &Grid<Tile>) -> Vec<Point> {
-    grid.points_with_item()
-        .filter_map(|(p, t)| {
-            Some(p).filter(|_| matches!(t, Tile::Roll)).filter(|p| {
-                let num_neighbors = p
-                    .adj_diag()
-                    .into_iter()
-                    .filter_map(|a| grid.get(a))
-                    .filter(|it| matches!(it, Tile::Roll))
-                    .count();
+// only there so `Grid` can build itself once `parse_grid` has checked every character
+#[doc(hidden)]
+impl From<char> for Tile {
+    fn from(c: char) -> Self {
+        Tile::parse(c).unwrap_or_else(|| panic!("Invalid tile character: {c}"))
+    }
+}
 
-                num_neighbors < 4
-            })
+/// The forklift rule: a roll can be reached if fewer than four of the eight cells around it hold
+/// rolls.
+#[must_use]
+pub fn forklift_rule() -> ErosionRule<Tile> {
+    ErosionRule {
+        neighbourhood: Neighbourhood::Moore,
+        threshold: 4,
+        solid: |t| matches!(t, Tile::Roll),
+    }
+}
+
+/// Finds every roll with fewer than four rolls in its eight neighbouring cells.
+#[must_use]
+pub fn find_accessible(grid: &Grid<Tile>) -> Vec<Point> {
+    forklift_rule().find_eroding(grid)
+}
+
+/// Repeatedly removes accessible rolls until none remain, returning how many were removed.
+pub fn remove_all_accessible(grid: &mut Grid<Tile>) -> usize {
+    removal_waves(grid).iter().map(|w| w.removed.len()).sum()
+}
+
+/// Removes accessible rolls round by round until none remain, returning what each round removed.
+pub fn removal_waves(grid: &mut Grid<Tile>) -> Vec<Wave> {
+    forklift_rule().erode(grid, &Tile::Empty)
+}
+
+/// How a cell looks in a frame of the removal.
+enum FrameCell {
+    Empty,
+    Roll,
+    JustRemoved,
+}
+
+/// The wave each removed roll went in, counting from 1.
+fn removal_rounds(waves: &[Wave]) -> HashMap<Point, usize> {
+    waves
+        .iter()
+        .enumerate()
+        .flat_map(|(i, w)| w.removed.iter().map(move |&p| (p, i + 1)))
+        .collect()
+}
+
+/// Lays out the cells of `grid` in rows as they stood after `round` waves, where `grid` is the
+/// layout before anything was removed.
+fn frame(
+    grid: &Grid<Tile>,
+    removed_in: &HashMap<Point, usize>,
+    round: usize,
+) -> Vec<Vec<FrameCell>> {
+    (0..grid.height())
+        .map(|y| {
+            (0..grid.width())
+                .map(|x| {
+                    let p = Point(x, y);
+                    match (grid[p], removed_in.get(&p)) {
+                        (Tile::Empty, _) => FrameCell::Empty,
+                        (Tile::Roll, Some(&r)) if r < round => FrameCell::Empty,
+                        (Tile::Roll, Some(&r)) if r == round => FrameCell::JustRemoved,
+                        (Tile::Roll, _) => FrameCell::Roll,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Draws the starting layout followed by the grid after each wave, with the rolls removed in that
+/// wave marked `x`. `grid` is the layout before anything was removed.
+#[must_use]
+pub fn ascii_frames(grid: &Grid<Tile>, waves: &[Wave]) -> Vec<String> {
+    let removed_in = removal_rounds(waves);
+    (0..=waves.len())
+        .map(|round| {
+            frame(grid, &removed_in, round)
+                .iter()
+                .flat_map(|row| {
+                    row.iter()
+                        .map(|cell| match cell {
+                            FrameCell::Empty => '.',
+                            FrameCell::Roll => '@',
+                            FrameCell::JustRemoved => 'x',
+                        })
+                        .chain(once('\n'))
+                })
+                .collect()
         })
         .collect()
 }
+
+/// Writes the grid after `round` waves as a binary PPM image, `scale` pixels to a cell, with the
+/// rolls removed in that round in red. Round 0 is the starting layout.
+pub fn write_ppm_frame(
+    grid: &Grid<Tile>,
+    waves: &[Wave],
+    round: usize,
+    scale: usize,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let rows = frame(grid, &removal_rounds(waves), round);
+    write!(
+        out,
+        "P6\n{} {}\n255\n",
+        grid.width() * scale,
+        grid.height() * scale
+    )?;
+    write_scaled_rows(
+        rows.iter().map(|row| {
+            row.iter().map(|cell| match cell {
+                FrameCell::Empty => [16, 16, 16],
+                FrameCell::Roll => [200, 200, 200],
+                FrameCell::JustRemoved => [220, 40, 40],
+            })
+        }),
+        scale,
+        out,
+    )
+}
@@ -1,52 +1,149 @@
-use aoc::parse_lines;
+use crate::error::{ParseError, SolveError, parse_num, try_parse_lines};
+use crate::range_set::RangeSet;
+use std::cmp::Reverse;
 
 pub struct Solution;
 
-impl aoc::Solution for Solution {
-    fn solve_1(&self, input: String) -> String {
-        let (ranges, ingredients) = parse_input(&input);
-        ingredients
+impl crate::Day for Solution {
+    type Input = (Vec<Range>, Vec<u64>);
+
+    fn parse(&self, input: &str) -> Result<(Vec<Range>, Vec<u64>), SolveError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn solve_1(&self, (ranges, ingredients): (Vec<Range>, Vec<u64>)) -> Result<String, SolveError> {
+        let fresh: RangeSet<u64> = ranges.into_iter().collect();
+        Ok(ingredients
             .into_iter()
-            .filter(|it| ranges.iter().any(|(start, end)| it >= start && it <= end))
+            .filter(|&it| fresh.contains(it))
             .count()
-            .to_string()
+            .to_string())
     }
 
-    fn solve_2(&self, input: String) -> String {
-        let (mut ranges, _) = parse_input(&input);
-        ranges.sort_by_key(|(s, _)| *s);
-        let merged_ranges: Vec<(u64, u64)> =
-            ranges.into_iter().fold(vec![], |mut acc, (start, end)| {
-                if let Some((_, last_end)) = acc.last_mut() {
-                    if start <= *last_end + 1 {
-                        *last_end = (*last_end).max(end);
-                    } else {
-                        acc.push((start, end));
-                    }
-                } else {
-                    acc.push((start, end));
-                }
-                acc
-            });
-        merged_ranges
-            .into_iter()
-            .map(|(s, e)| e - s + 1)
-            .sum::<u64>()
-            .to_string()
+    fn solve_2(&self, (ranges, _): (Vec<Range>, Vec<u64>)) -> Result<String, SolveError> {
+        let fresh: RangeSet<u64> = ranges.into_iter().collect();
+        Ok(fresh.len().to_string())
+    }
+}
+
+/// Inclusive `(start, end)` range of fresh ingredient IDs.
+pub type Range = (u64, u64);
+
+/// Which of the fresh ID ranges cover each ingredient, along with the ranges that stand out.
+/// Ranges are referred to by their position in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreshnessReport {
+    pub ingredients: Vec<IngredientReport>,
+    /// Ranges that none of the ingredients fall in.
+    pub unused_ranges: Vec<usize>,
+    /// Every `(inner, outer)` pair where range `inner` lies entirely within range `outer`, in
+    /// order. Of two identical ranges, the later one is the inner.
+    pub contained_ranges: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IngredientReport {
+    pub id: u64,
+    /// Every range the ID falls in, in input order.
+    pub covering_ranges: Vec<usize>,
+}
+
+impl IngredientReport {
+    #[must_use]
+    pub fn is_fresh(&self) -> bool {
+        !self.covering_ranges.is_empty()
     }
 }
 
-fn parse_input(input: &str) -> (Vec<(u64, u64)>, Vec<u64>) {
-    let (ranges_str, ingr_str) = input.split_once("\n\n").unwrap();
-    let ranges: Vec<(u64, u64)> = ranges_str
-        .lines()
-        .map(|line| {
-            let (start_str, end_str) = line.split_once('-').unwrap();
-            let start: u64 = start_str.parse().unwrap();
-            let end: u64 = end_str.parse().unwrap();
-            (start, end)
+/// Works out which ranges cover each ingredient, which ranges no ingredient falls in, and which
+/// ranges are made redundant by another.
+///
+/// # Panics
+///
+/// Never: every range lies within the merged set of all of them.
+#[must_use]
+pub fn freshness_report(ranges: &[Range], ingredients: &[u64]) -> FreshnessReport {
+    // an ingredient can only be covered by the ranges that merge into the one holding it, so
+    // those are all that need searching
+    let merged: RangeSet<u64> = ranges.iter().copied().collect();
+    let mut members: Vec<Vec<usize>> = vec![vec![]; merged.ranges().len()];
+    // longest first among equal starts, so containers come before what they contain
+    let mut by_start: Vec<usize> = (0..ranges.len()).collect();
+    by_start.sort_unstable_by_key(|&i| (ranges[i].0, Reverse(ranges[i].1), i));
+    for &i in &by_start {
+        let block = merged
+            .position(ranges[i].0)
+            .expect("every range is in the merged set");
+        members[block].push(i);
+    }
+
+    let mut used = vec![false; ranges.len()];
+    let ingredients = ingredients
+        .iter()
+        .map(|&id| {
+            let block = merged.position(id).map_or(&[][..], |b| &members[b][..]);
+            let started = block.partition_point(|&i| ranges[i].0 <= id);
+            let mut covering_ranges: Vec<usize> = block[..started]
+                .iter()
+                .copied()
+                .filter(|&i| ranges[i].1 >= id)
+                .collect();
+            covering_ranges.sort_unstable();
+            for &i in &covering_ranges {
+                used[i] = true;
+            }
+            IngredientReport {
+                id,
+                covering_ranges,
+            }
         })
         .collect();
-    let ingredients: Vec<u64> = parse_lines(ingr_str).collect();
-    (ranges, ingredients)
+
+    // a range can only lie within ranges ahead of it in its own block
+    let mut contained_ranges = vec![];
+    for block in &members {
+        for (n, &inner) in block.iter().enumerate() {
+            contained_ranges.extend(
+                block[..n]
+                    .iter()
+                    .filter(|&&outer| ranges[outer].1 >= ranges[inner].1)
+                    .map(|&outer| (inner, outer)),
+            );
+        }
+    }
+    contained_ranges.sort_unstable();
+
+    FreshnessReport {
+        ingredients,
+        unused_ranges: (0..ranges.len()).filter(|&i| !used[i]).collect(),
+        contained_ranges,
+    }
+}
+
+/// Parses the fresh ID ranges and the available ingredient IDs.
+pub fn parse_input(input: &str) -> Result<(Vec<Range>, Vec<u64>), ParseError> {
+    let (ranges_str, ingr_str) = input.split_once("\n\n").ok_or_else(|| {
+        ParseError::new(
+            input.lines().count() + 1,
+            1,
+            "missing blank line between ranges and ingredients",
+        )
+    })?;
+    let ranges: Vec<Range> = try_parse_lines(ranges_str, |line| {
+        let (start_str, end_str) = line.split_once('-').ok_or_else(|| {
+            ParseError::at_column(1, format!("expected 'start-end', found '{line}'"))
+        })?;
+        let start: u64 = parse_num(line, start_str)?;
+        let end: u64 = parse_num(line, end_str)?;
+        if start > end {
+            return Err(ParseError::at_column(
+                1,
+                format!("range start {start} is after its end {end}"),
+            ));
+        }
+        Ok((start, end))
+    })?;
+    let ingredients: Vec<u64> = try_parse_lines(ingr_str, |line| parse_num(line, line))
+        .map_err(|e| e.offset(ranges_str.lines().count() + 1))?;
+    Ok((ranges, ingredients))
 }
@@ -1,73 +1,195 @@
+use crate::error::{ParseError, SolveError, column_of, parse_num, try_parse_lines};
+use crate::expression::{Expression, Op};
 use std::borrow::Borrow;
 
 pub struct Solution;
 
-impl aoc::Solution for Solution {
-    fn solve_1(&self, input: String) -> String {
-        let lines: Vec<_> = input.lines().collect();
-        let nums: Vec<Vec<u64>> = lines[0..lines.len() - 1]
-            .iter()
-            .map(|line| {
-                line.split_ascii_whitespace()
-                    .map(|it| it.parse().unwrap())
-                    .collect()
-            })
-            .collect();
-        let ops: Vec<_> = lines.last().unwrap().split_ascii_whitespace().collect();
-
-        let mut sum = 0;
-        for i in 0..ops.len() {
-            let op = ops[i];
-            let col = match op {
-                "+" => nums.iter().map(|row| row[i]).sum::<u64>(),
-                "*" => nums.iter().map(|row| row[i]).product::<u64>(),
-                _ => unreachable!(),
-            };
-            sum += col;
-        }
-        sum.to_string()
+impl crate::Day for Solution {
+    type Input = Worksheet;
+
+    fn parse(&self, input: &str) -> Result<Worksheet, SolveError> {
+        split_worksheet(input)?;
+        Ok(Worksheet {
+            rows: read_rows(input),
+            columns: read_columns(input),
+        })
     }
 
-    fn solve_2(&self, input: String) -> String {
-        let lines: Vec<_> = input.lines().collect();
+    fn solve_1(&self, worksheet: Worksheet) -> Result<String, SolveError> {
+        Ok(grand_total(&worksheet.rows?)?.to_string())
+    }
 
-        let lines_chars = lines[0..lines.len() - 1]
-            .iter()
-            .map(|s| s.chars().collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-        let transpose_lines = transpose(&lines_chars)
-            .into_iter()
-            .map(|lcs| lcs.into_iter().collect::<String>())
-            .collect::<Vec<_>>();
-
-        transpose_lines
-            .split(|line| line.trim().is_empty())
-            .zip(lines.last().unwrap().split_ascii_whitespace())
-            .map(|(nums_str, ops)| {
-                let nums = nums_str.iter().map(|s| s.trim().parse::<u64>().unwrap());
-                match ops {
-                    "+" => nums.sum::<u64>(),
-                    "*" => nums.product(),
-                    _ => unreachable!(),
-                }
-            })
-            .sum::<u64>()
-            .to_string()
+    fn solve_2(&self, worksheet: Worksheet) -> Result<String, SolveError> {
+        Ok(grand_total(&worksheet.columns?)?.to_string())
     }
 }
 
-#[allow(clippy::needless_range_loop)]
-fn transpose<V: Borrow<[T]>, T: Copy + Default>(matrix: &[V]) -> Vec<Vec<T>> {
-    if matrix.is_empty() {
-        return vec![];
-    }
-    let row_count = matrix.len();
-    let col_count = matrix[0].borrow().len();
-    let mut transposed = vec![vec![T::default(); row_count]; col_count];
-    for r in 0..row_count {
-        for c in 0..col_count {
-            transposed[c][r] = matrix[r].borrow()[c];
+/// The worksheet's problems read both ways. A worksheet can make sense one way and not the
+/// other, so each part only fails if its own reading does.
+pub struct Worksheet {
+    rows: Result<Vec<Expression>, ParseError>,
+    columns: Result<Vec<Expression>, ParseError>,
+}
+
+/// An operator from the bottom row of the worksheet and the column it sits in.
+type PlacedOp = (usize, Op);
+
+/// A worksheet problem along with its answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub problem: Expression,
+    pub value: u128,
+}
+
+/// Works out every problem on the worksheet, failing on the first one that can't be answered.
+pub fn breakdown(problems: &[Expression]) -> Result<Vec<Answer>, SolveError> {
+    problems
+        .iter()
+        .enumerate()
+        .map(|(i, problem)| {
+            let value = problem.evaluate().map_err(|e| {
+                SolveError::Unsolvable(format!("problem {} ({problem}): {e}", i + 1).into())
+            })?;
+            Ok(Answer {
+                problem: problem.clone(),
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Sums the answers to every problem on the worksheet.
+pub fn grand_total(problems: &[Expression]) -> Result<u128, SolveError> {
+    breakdown(problems)?
+        .iter()
+        .try_fold(0u128, |total, answer| total.checked_add(answer.value))
+        .ok_or(SolveError::Unsolvable("grand total overflows u128".into()))
+}
+
+/// Reads the worksheet problems with each number left to right along a row.
+pub fn read_rows(input: &str) -> Result<Vec<Expression>, ParseError> {
+    let (nums_str, ops) = split_worksheet(input)?;
+    let nums: Vec<Vec<u128>> = try_parse_lines(nums_str, |line| {
+        let row: Vec<u128> = line
+            .split_ascii_whitespace()
+            .map(|it| parse_num(line, it))
+            .collect::<Result<_, _>>()?;
+        if row.len() != ops.len() {
+            return Err(ParseError::at_column(
+                line.len() + 1,
+                format!("expected {} numbers, found {}", ops.len(), row.len()),
+            ));
         }
+        Ok(row)
+    })?;
+
+    Ok(ops
+        .into_iter()
+        .enumerate()
+        .map(|(i, (_, op))| Expression {
+            op,
+            operands: nums.iter().map(|row| row[i]).collect(),
+        })
+        .collect())
+}
+
+/// Reads the worksheet problems with each number top to bottom down a column, taking the
+/// numbers right to left. Each problem starts at the column of its operator and runs up to the
+/// next one. Short lines are treated as if padded with spaces. Numbers left of the first operator
+/// and operators with no numbers of their own are errors.
+pub fn read_columns(input: &str) -> Result<Vec<Expression>, ParseError> {
+    let (nums_str, ops) = split_worksheet(input)?;
+
+    let lines_chars = nums_str
+        .lines()
+        .map(|s| s.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let columns = transpose(&lines_chars, ' ')
+        .into_iter()
+        .enumerate()
+        .map(|(c, lcs)| {
+            let col = lcs.into_iter().collect::<String>();
+            let col = col.trim();
+            if col.is_empty() {
+                return Ok(None);
+            }
+            col.parse::<u128>().map(Some).map_err(|e| {
+                ParseError::at_column(c + 1, format!("invalid number '{col}' in column: {e}"))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let first_op = ops.first().map_or(columns.len(), |&(c, _)| c);
+    if let Some(c) = (0..first_op.min(columns.len())).find(|&c| columns[c].is_some()) {
+        let line = lines_chars
+            .iter()
+            .position(|chars| chars.get(c).is_some_and(|ch| !ch.is_whitespace()))
+            .unwrap_or(0);
+        return Err(ParseError::new(
+            line + 1,
+            c + 1,
+            "number comes before the first operator",
+        ));
     }
-    transposed
+
+    let starts: Vec<usize> = ops.iter().map(|&(c, _)| c).collect();
+    ops.iter()
+        .enumerate()
+        .map(|(i, &(start, op))| {
+            let end = starts
+                .get(i + 1)
+                .map_or(columns.len(), |&e| e.min(columns.len()));
+            let operands: Vec<u128> = columns[start.min(end)..end]
+                .iter()
+                .rev()
+                .flatten()
+                .copied()
+                .collect();
+            if operands.is_empty() {
+                return Err(ParseError::new(
+                    lines_chars.len() + 1,
+                    start + 1,
+                    format!("no numbers under operator '{op}'"),
+                ));
+            }
+            Ok(Expression { op, operands })
+        })
+        .collect()
+}
+
+/// Splits the worksheet into its lines of numbers and the operators from the bottom row, each
+/// with the column it starts in.
+fn split_worksheet(input: &str) -> Result<(&str, Vec<PlacedOp>), ParseError> {
+    let (nums_str, ops_line) = input
+        .trim_end_matches('\n')
+        .rsplit_once('\n')
+        .ok_or_else(|| ParseError::at_column(1, "worksheet needs numbers and operators"))?;
+    let ops = ops_line
+        .split_ascii_whitespace()
+        .map(|token| {
+            let column = column_of(ops_line, token);
+            Op::parse(token)
+                .map(|op| (column - 1, op))
+                .ok_or_else(|| ParseError::at_column(column, format!("unknown operator '{token}'")))
+        })
+        .collect::<Result<_, _>>()
+        .map_err(|e: ParseError| e.offset(nums_str.lines().count()))?;
+    Ok((nums_str, ops))
+}
+
+/// Swaps rows and columns, filling in for rows shorter than the longest.
+fn transpose<V: Borrow<[T]>, T: Copy>(matrix: &[V], fill: T) -> Vec<Vec<T>> {
+    let col_count = matrix
+        .iter()
+        .map(|row| row.borrow().len())
+        .max()
+        .unwrap_or(0);
+    (0..col_count)
+        .map(|c| {
+            matrix
+                .iter()
+                .map(|row| row.borrow().get(c).copied().unwrap_or(fill))
+                .collect()
+        })
+        .collect()
 }
@@ -1,90 +1,481 @@
-use aoc::{Counter, Grid, Point};
-use std::collections::HashSet;
+use crate::error::{SolveError, parse_grid};
+use crate::image::write_scaled_rows;
+use aoc::{Grid, Point};
+use num_bigint::BigUint;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::num::NonZeroU64;
 
 pub struct Solution;
 
-impl aoc::Solution for Solution {
-    fn solve_1(&self, input: String) -> String {
-        let map: Grid<Tile> = input.parse().unwrap();
-        let start_p = map
-            .points_with_item()
-            .find(|(_, t)| matches!(t, Tile::Start))
-            .unwrap()
-            .0;
-        let mut split_count = 0;
-        let mut beams = HashSet::new();
-        beams.insert(start_p.0);
-        let mut y = start_p.1;
-        loop {
-            y += 1;
-            if y >= map.height() {
-                break;
+impl crate::Day for Solution {
+    type Input = Grid<Tile>;
+
+    fn parse(&self, input: &str) -> Result<Grid<Tile>, SolveError> {
+        Ok(parse_grid(input, Tile::parse)?)
+    }
+
+    fn solve_1(&self, map: Grid<Tile>) -> Result<String, SolveError> {
+        Ok(count_splits(&map, EdgePolicy::default())?.to_string())
+    }
+
+    fn solve_2(&self, map: Grid<Tile>) -> Result<String, SolveError> {
+        Ok(timelines(&map, EdgePolicy::default(), Counting::Exact)?
+            .total()
+            .to_string())
+    }
+}
+
+pub enum Tile {
+    Empty,
+    Start,
+    /// Splits a beam into two, one column either side.
+    Splitter,
+    /// Splits a beam across this many columns around it, skipping its own column when the
+    /// count is even.
+    Fan(u8),
+    /// Stops a beam dead.
+    Absorber,
+    /// Sends a beam off to the left along its row.
+    MirrorLeft,
+    /// Sends a beam off to the right along its row.
+    MirrorRight,
+}
+
+impl Tile {
+    #[must_use]
+    pub fn parse(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Tile::Empty),
+            'S' => Some(Tile::Start),
+            '^' => Some(Tile::Splitter),
+            '#' => Some(Tile::Absorber),
+            '<' => Some(Tile::MirrorLeft),
+            '>' => Some(Tile::MirrorRight),
+            '3'..='9' => c
+                .to_digit(10)
+                .and_then(|n| u8::try_from(n).ok())
+                .map(Tile::Fan),
+            _ => None,
+        }
+    }
+
+    fn splits(&self) -> bool {
+        matches!(self, Tile::Splitter | Tile::Fan(_))
+    }
+
+    /// The columns, relative to this one, that a splitting tile sends beams down.
+    fn split_offsets(&self) -> Vec<isize> {
+        match *self {
+            Tile::Splitter => vec![-1, 1],
+            Tile::Fan(n) => {
+                let reach = isize::from(n / 2);
+                (-reach..=reach)
+                    .filter(|&dx| dx != 0 || n % 2 == 1)
+                    .collect()
             }
-            let mut new_beams = HashSet::new();
-            for x in beams {
-                match &map[Point(x, y)] {
-                    Tile::Empty => {
-                        new_beams.insert(x);
-                    }
-                    Tile::Splitter => {
-                        split_count += 1;
-                        new_beams.insert(x - 1);
-                        new_beams.insert(x + 1);
-                    }
-                    Tile::Start => panic!("Beam hit start tile again!"),
+            _ => vec![],
+        }
+    }
+}
+
+// only there so `Grid` can build itself once `parse_grid` has checked every character
+#[doc(hidden)]
+impl From<char> for Tile {
+    fn from(c: char) -> Self {
+        Tile::parse(c).unwrap_or_else(|| panic!("Unknown tile character: {c}"))
+    }
+}
+
+fn find_start(map: &Grid<Tile>) -> Result<Point, SolveError> {
+    map.points_with_item()
+        .find_map(|(p, t)| Some(p).filter(|_| matches!(t, Tile::Start)))
+        .ok_or(SolveError::Unsolvable(
+            "no start tile 'S' in manifold".into(),
+        ))
+}
+
+/// What happens to a beam that goes off the side of the manifold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgePolicy {
+    /// The beam is lost, along with its timelines.
+    Drop,
+    /// The beam leaves the manifold, and its timelines end there.
+    #[default]
+    CountEscaped,
+    /// The beam comes back in on the opposite side.
+    Wrap,
+}
+
+/// Where a beam moving sideways ends up.
+enum Exit {
+    Column(usize),
+    Escaped,
+}
+
+impl EdgePolicy {
+    /// Moves a beam `dx` columns over from column `x` on a manifold `width` columns wide. `None`
+    /// means it's gone.
+    fn step(self, x: usize, dx: isize, width: usize) -> Option<Exit> {
+        match x.checked_add_signed(dx).filter(|&n| n < width) {
+            Some(n) => Some(Exit::Column(n)),
+            None => match self {
+                EdgePolicy::Drop => None,
+                EdgePolicy::CountEscaped => Some(Exit::Escaped),
+                EdgePolicy::Wrap => {
+                    let shift = dx.unsigned_abs() % width;
+                    Some(Exit::Column(if dx < 0 {
+                        (x + width - shift) % width
+                    } else {
+                        (x + shift) % width
+                    }))
                 }
-            }
-            beams = new_beams;
+            },
         }
-        split_count.to_string()
-    }
-
-    fn solve_2(&self, input: String) -> String {
-        let map: Grid<Tile> = input.parse().unwrap();
-        let start_p = map
-            .points_with_item()
-            .find_map(|(p, t)| Some(p).filter(|_| matches!(t, Tile::Start)))
-            .unwrap();
-        let mut beams = Counter::new();
-        beams.count(start_p.0);
-        let mut y = start_p.1;
-        loop {
-            y += 1;
-            if y >= map.height() {
-                break;
-            }
-            let mut new_beams = Counter::new();
-            for (x, n) in beams {
-                match &map[Point(x, y)] {
-                    Tile::Empty => {
-                        new_beams.count_n(x, n);
-                    }
-                    Tile::Splitter => {
-                        new_beams.count_n(x - 1, n);
-                        new_beams.count_n(x + 1, n);
-                    }
-                    Tile::Start => panic!("Beam hit start tile again!"),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Heading {
+    Down,
+    Left,
+    Right,
+}
+
+/// A beam entering a cell, travelling in some direction.
+type Beam = (Point, Heading);
+
+/// Where a beam goes after the cell it enters.
+enum Next {
+    Beam(Beam),
+    /// Out the bottom of the manifold, in this column.
+    Bottom(usize),
+    Escaped,
+    Absorbed,
+}
+
+/// Works out where a beam entering `p` heading `heading` goes next.
+fn successors(
+    map: &Grid<Tile>,
+    width: usize,
+    policy: EdgePolicy,
+    (p, heading): Beam,
+) -> Result<Vec<Next>, SolveError> {
+    let Point(x, y) = p;
+    let sideways = |dx: isize, heading: Heading| match policy.step(x, dx, width) {
+        Some(Exit::Column(n)) => Some(Next::Beam((Point(n, y), heading))),
+        Some(Exit::Escaped) => Some(Next::Escaped),
+        None => None,
+    };
+    let down = |x: usize| {
+        if y + 1 >= map.height() {
+            Next::Bottom(x)
+        } else {
+            Next::Beam((Point(x, y + 1), Heading::Down))
+        }
+    };
+    let tile = &map[p];
+    Ok(match (tile, heading) {
+        (Tile::Start, _) => return Err(beam_hit_start(x, y)),
+        (Tile::Empty, Heading::Down) => vec![down(x)],
+        (Tile::Empty, Heading::Left) | (Tile::MirrorLeft, _) => {
+            sideways(-1, Heading::Left).into_iter().collect()
+        }
+        (Tile::Empty, Heading::Right) | (Tile::MirrorRight, _) => {
+            sideways(1, Heading::Right).into_iter().collect()
+        }
+        (Tile::Absorber, _) => vec![Next::Absorbed],
+        (Tile::Splitter | Tile::Fan(_), _) => tile
+            .split_offsets()
+            .into_iter()
+            .filter_map(|dx| match policy.step(x, dx, width)? {
+                Exit::Column(n) => Some(down(n)),
+                Exit::Escaped => Some(Next::Escaped),
+            })
+            .collect(),
+    })
+}
+
+/// Every beam reachable from the start.
+struct Exploration {
+    start: Point,
+    /// Where the start sends its beam.
+    first: Vec<Next>,
+    /// The beams reached, each before all of those it leads to unless there's a loop.
+    order: Vec<Beam>,
+    next: HashMap<Beam, Vec<Next>>,
+    /// A beam that leads back to itself, if there are any.
+    cycle: Option<Beam>,
+}
+
+fn explore(map: &Grid<Tile>, policy: EdgePolicy) -> Result<Exploration, SolveError> {
+    let start = find_start(map)?;
+    let width = map.width();
+    let first = if start.1 + 1 >= map.height() {
+        vec![Next::Bottom(start.0)]
+    } else {
+        vec![Next::Beam((Point(start.0, start.1 + 1), Heading::Down))]
+    };
+
+    // depth-first, so reversing the order beams finish in sorts them; `false` while a beam's
+    // successors are still being explored, which is how loops are caught
+    let mut finished: HashMap<Beam, bool> = HashMap::new();
+    let mut next: HashMap<Beam, Vec<Next>> = HashMap::new();
+    let mut order = vec![];
+    let mut cycle = None;
+    for root in &first {
+        let &Next::Beam(root) = root else { continue };
+        if finished.contains_key(&root) {
+            continue;
+        }
+        finished.insert(root, false);
+        next.insert(root, successors(map, width, policy, root)?);
+        let mut stack = vec![(root, 0)];
+        while let Some((beam, i)) = stack.last_mut() {
+            let child = next[beam]
+                .iter()
+                .filter_map(|n| match n {
+                    Next::Beam(b) => Some(*b),
+                    _ => None,
+                })
+                .nth(*i);
+            *i += 1;
+            let Some(child) = child else {
+                let beam = *beam;
+                finished.insert(beam, true);
+                order.push(beam);
+                stack.pop();
+                continue;
+            };
+            match finished.get(&child) {
+                None => {
+                    finished.insert(child, false);
+                    next.insert(child, successors(map, width, policy, child)?);
+                    stack.push((child, 0));
                 }
+                Some(false) => cycle = cycle.or(Some(child)),
+                Some(true) => {}
             }
-            beams = new_beams;
         }
-        beams.total().to_string()
     }
+    order.reverse();
+    Ok(Exploration {
+        start,
+        first,
+        order,
+        next,
+        cycle,
+    })
 }
 
-enum Tile {
-    Empty,
-    Start,
-    Splitter,
+/// Counts the splitters a beam reaches on its way from the start tile.
+pub fn count_splits(map: &Grid<Tile>, policy: EdgePolicy) -> Result<usize, SolveError> {
+    let exploration = explore(map, policy)?;
+    let splitters: HashSet<Point> = exploration
+        .order
+        .iter()
+        .map(|&(p, _)| p)
+        .filter(|&p| map[p].splits())
+        .collect();
+    Ok(splitters.len())
 }
 
-impl From<char> for Tile {
-    fn from(c: char) -> Self {
-        match c {
-            '.' => Tile::Empty,
-            'S' => Tile::Start,
-            '^' => Tile::Splitter,
-            _ => panic!("Unknown tile character: {c}"),
+/// How timelines are counted. The count can double at every row of splitters, so it's either
+/// kept exact as a big integer or reduced modulo some number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Counting {
+    #[default]
+    Exact,
+    Modulo(NonZeroU64),
+}
+
+impl Counting {
+    fn add(self, total: &mut BigUint, n: &BigUint) {
+        *total += n;
+        if let Counting::Modulo(m) = self {
+            *total %= m.get();
+        }
+    }
+}
+
+/// The timelines a beam splits into on its way down the manifold.
+pub struct Timelines {
+    /// How many timelines end in each column of the bottom row.
+    pub columns: HashMap<usize, BigUint>,
+    /// How many left the manifold sideways, under [`EdgePolicy::CountEscaped`].
+    pub escaped: BigUint,
+    /// How many ended in an absorber.
+    pub absorbed: BigUint,
+    pub counting: Counting,
+}
+
+impl Timelines {
+    #[must_use]
+    pub fn total(&self) -> BigUint {
+        let mut total = self.escaped.clone();
+        self.counting.add(&mut total, &self.absorbed);
+        for n in self.columns.values() {
+            self.counting.add(&mut total, n);
+        }
+        total
+    }
+}
+
+/// Counts the timelines ending in each column of the bottom row of the manifold, and those
+/// escaping off the sides or stopped by absorbers. Fails if a beam can loop forever, since there
+/// would be no end of timelines.
+pub fn timelines(
+    map: &Grid<Tile>,
+    policy: EdgePolicy,
+    counting: Counting,
+) -> Result<Timelines, SolveError> {
+    propagate(map, policy, counting, |_, _| {})
+}
+
+/// Sends timelines through the manifold, calling `visit` with each cell they reach and how many
+/// of them reach it, once for each direction they reach it from.
+fn propagate(
+    map: &Grid<Tile>,
+    policy: EdgePolicy,
+    counting: Counting,
+    mut visit: impl FnMut(Point, &BigUint),
+) -> Result<Timelines, SolveError> {
+    let exploration = explore(map, policy)?;
+    if let Some((Point(x, y), _)) = exploration.cycle {
+        return Err(SolveError::Unsolvable(
+            format!("beam loops forever through ({x}, {y})").into(),
+        ));
+    }
+
+    let mut timelines = Timelines {
+        columns: HashMap::new(),
+        escaped: BigUint::ZERO,
+        absorbed: BigUint::ZERO,
+        counting,
+    };
+    let mut beams: HashMap<Beam, BigUint> = HashMap::new();
+    let mut follow = |next: &[Next], n: &BigUint, beams: &mut HashMap<Beam, BigUint>| {
+        for next in next {
+            let total = match next {
+                Next::Beam(beam) => beams.entry(*beam).or_default(),
+                Next::Bottom(x) => timelines.columns.entry(*x).or_default(),
+                Next::Escaped => &mut timelines.escaped,
+                Next::Absorbed => &mut timelines.absorbed,
+            };
+            counting.add(total, n);
         }
+    };
+
+    let one = BigUint::from(1u8);
+    visit(exploration.start, &one);
+    follow(&exploration.first, &one, &mut beams);
+    // every beam leading into this one has already been counted
+    for beam in &exploration.order {
+        let n = beams.remove(beam).unwrap_or_default();
+        visit(beam.0, &n);
+        follow(&exploration.next[beam], &n, &mut beams);
     }
+    Ok(timelines)
+}
+
+/// How many timelines passed through every cell of the manifold.
+pub struct TimelineMap {
+    pub counts: HashMap<Point, BigUint>,
+    /// The splitters at least one beam reached.
+    pub splitters_hit: HashSet<Point>,
+    pub timelines: Timelines,
+}
+
+/// Follows the timelines down the manifold like [`timelines`], keeping the count for every cell
+/// on the way rather than just the bottom row.
+pub fn timeline_map(
+    map: &Grid<Tile>,
+    policy: EdgePolicy,
+    counting: Counting,
+) -> Result<TimelineMap, SolveError> {
+    let mut counts = HashMap::new();
+    let timelines = propagate(map, policy, counting, |p, n| {
+        counting.add(counts.entry(p).or_default(), n);
+    })?;
+    let splitters_hit = counts
+        .keys()
+        .copied()
+        .filter(|&p| map[p].splits())
+        .collect();
+    Ok(TimelineMap {
+        counts,
+        splitters_hit,
+        timelines,
+    })
+}
+
+impl TimelineMap {
+    /// Draws the manifold with the beams' paths marked: `|` where a beam passed, `^` or the fan's
+    /// digit for a splitter that was hit and `-` for one no beam reached. Other tiles are drawn as
+    /// they are.
+    #[must_use]
+    pub fn render_ascii(&self, map: &Grid<Tile>) -> String {
+        let width = map.width();
+        let mut out = String::new();
+        for y in 0..map.height() {
+            for x in 0..width {
+                let p = Point(x, y);
+                out.push(match map[p] {
+                    Tile::Start => 'S',
+                    Tile::Splitter if self.splitters_hit.contains(&p) => '^',
+                    Tile::Fan(n) if self.splitters_hit.contains(&p) => char::from(b'0' + n),
+                    Tile::Splitter | Tile::Fan(_) => '-',
+                    Tile::Absorber => '#',
+                    Tile::MirrorLeft => '<',
+                    Tile::MirrorRight => '>',
+                    Tile::Empty if self.counts.contains_key(&p) => '|',
+                    Tile::Empty => '.',
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Writes the timeline counts as a binary PGM image, `scale` pixels to a cell. Brightness
+    /// follows the log of the count, since counts can double at every splitter.
+    pub fn write_heatmap(
+        &self,
+        map: &Grid<Tile>,
+        scale: usize,
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        let width = map.width();
+        let brightest = self.counts.values().map(log_count).fold(0.0, f64::max);
+        write!(out, "P5\n{} {}\n255\n", width * scale, map.height() * scale)?;
+        write_scaled_rows(
+            (0..map.height()).map(|y| {
+                (0..width).map(move |x| {
+                    let count = self.counts.get(&Point(x, y)).map_or(0.0, log_count);
+                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // in 0..=255
+                    let shade = if brightest > 0.0 {
+                        (255.0 * count / brightest).round() as u8
+                    } else {
+                        0
+                    };
+                    [shade]
+                })
+            }),
+            scale,
+            out,
+        )
+    }
+}
+
+#[allow(clippy::cast_precision_loss)] // only used for shading
+fn log_count(count: &BigUint) -> f64 {
+    match u64::try_from(count) {
+        Ok(n) => (n as f64).ln_1p(),
+        // close enough once the count is this big
+        Err(_) => count.bits() as f64 * std::f64::consts::LN_2,
+    }
+}
+
+fn beam_hit_start(x: usize, y: usize) -> SolveError {
+    SolveError::Unsolvable(format!("beam hit a second start tile at ({x}, {y})").into())
 }
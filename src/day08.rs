@@ -1,52 +1,59 @@
-use aoc::{Point3D, pairs_without_dups, parse_lines_with};
+use crate::disjoint_set::DisjointSets;
+use crate::error::{ParseError, SolveError, parse_num, try_parse_lines};
+use aoc::{Point3D, pairs_without_dups};
 use std::collections::{HashMap, HashSet};
 
 pub struct Solution;
 
-impl aoc::Solution for Solution {
-    fn solve_1(&self, input: String) -> String {
-        let solver = Solver::new(&input);
-        let mut circuits = solver.solve_part_1();
+impl crate::Day for Solution {
+    type Input = Solver;
+
+    fn parse(&self, input: &str) -> Result<Solver, SolveError> {
+        Ok(Solver::new(input)?)
+    }
+
+    fn solve_1(&self, solver: Solver) -> Result<String, SolveError> {
+        let mut circuits = solver.solve_part_1(1000);
 
         circuits.sort_by_key(|it| -i32::try_from(it.len()).unwrap());
-        (circuits[0].len() * circuits[1].len() * circuits[2].len()).to_string()
+        match &circuits[..] {
+            [a, b, c, ..] => Ok((a.len() * b.len() * c.len()).to_string()),
+            _ => Err(SolveError::Unsolvable(
+                "need at least three junction boxes".into(),
+            )),
+        }
     }
 
-    fn solve_2(&self, input: String) -> String {
-        let solver = Solver::new(&input);
-        let (last_p, last_q) = solver.solve_part_2();
+    fn solve_2(&self, solver: Solver) -> Result<String, SolveError> {
+        let (last_p, last_q) = solver.solve_part_2()?;
 
-        (last_p.0 * last_q.0).to_string()
+        Ok((last_p.0 * last_q.0).to_string())
     }
 }
 
-struct Solver {
+/// Connects junction boxes into circuits, closest pairs first.
+pub struct Solver {
     points: Vec<Point3D>,
-    circuits: Vec<HashSet<Point3D>>,
-    points_to_circuit: HashMap<Point3D, usize>,
+    indices: HashMap<Point3D, usize>,
+    circuits: DisjointSets,
 }
 
 impl Solver {
-    fn new(input: &str) -> Self {
-        let points: Vec<Point3D> = parse_lines_with(input, parse_point).collect();
-        let circuits: Vec<HashSet<Point3D>> = points
-            .iter()
-            .map(|&p| {
-                let mut set = HashSet::new();
-                set.insert(p);
-                set
-            })
-            .collect();
-        let points_to_circuit: HashMap<Point3D, usize> =
+    pub fn new(input: &str) -> Result<Self, ParseError> {
+        let points: Vec<Point3D> = try_parse_lines(input, parse_point)?;
+        let indices: HashMap<Point3D, usize> =
             points.iter().enumerate().map(|(a, b)| (*b, a)).collect();
-        Self {
+        let circuits = DisjointSets::new(points.len());
+        Ok(Self {
             points,
+            indices,
             circuits,
-            points_to_circuit,
-        }
+        })
     }
 
-    fn point_pairs(&self) -> Vec<(Point3D, Point3D)> {
+    /// Returns every pair of junction boxes, sorted by straight-line distance.
+    #[must_use]
+    pub fn point_pairs(&self) -> Vec<(Point3D, Point3D)> {
         let mut point_pairs: Vec<(Point3D, Point3D)> = pairs_without_dups(&self.points)
             .map(|t| (*t.0, *t.1))
             .collect();
@@ -54,45 +61,48 @@ impl Solver {
         point_pairs
     }
 
-    fn solve_part_1(mut self) -> Vec<HashSet<Point3D>> {
-        for (p, q) in &self.point_pairs()[0..1000] {
+    /// Connects the `connections` closest pairs and returns the resulting circuits.
+    #[must_use]
+    pub fn solve_part_1(mut self, connections: usize) -> Vec<HashSet<Point3D>> {
+        for (p, q) in self.point_pairs().iter().take(connections) {
             self.connect(*p, *q);
         }
-        self.circuits
+        let mut circuits: HashMap<usize, HashSet<Point3D>> = HashMap::new();
+        for (i, &p) in self.points.iter().enumerate() {
+            circuits.entry(self.circuits.find(i)).or_default().insert(p);
+        }
+        circuits.into_values().collect()
     }
 
-    fn solve_part_2(mut self) -> (Point3D, Point3D) {
+    /// Connects pairs until every box is in one circuit and returns the pair that completed it.
+    pub fn solve_part_2(mut self) -> Result<(Point3D, Point3D), SolveError> {
         for (p, q) in self.point_pairs() {
-            self.connect(p, q);
-
-            if self.circuits.iter().filter(|c| !c.is_empty()).count() == 1 {
-                return (p, q);
+            if self.connect(p, q) && self.circuits.components() == 1 {
+                return Ok((p, q));
             }
         }
-        panic!("All points not connected");
+        Err(SolveError::Unsolvable("All points not connected".into()))
     }
 
-    fn connect(&mut self, p: Point3D, q: Point3D) {
-        let circuit_p = self.points_to_circuit[&p];
-        let circuit_q = self.points_to_circuit[&q];
-        if circuit_p != circuit_q {
-            let set_p = &self.circuits[circuit_p];
-            let set_q = &self.circuits[circuit_q];
-            let new_set = set_p.union(set_q).copied().collect::<HashSet<_>>();
-            for &point in &new_set {
-                self.points_to_circuit.insert(point, circuit_p);
-            }
-            self.circuits[circuit_p] = new_set;
-            self.circuits[circuit_q].clear();
-        }
+    /// Merges the circuits containing `p` and `q`, returning `false` if they were already the
+    /// same circuit.
+    pub fn connect(&mut self, p: Point3D, q: Point3D) -> bool {
+        self.circuits.union(self.indices[&p], self.indices[&q])
     }
 }
 
-fn parse_point(line: &str) -> Point3D {
-    let mut parts = line.split(',').map(|s| s.trim().parse().unwrap());
-    Point3D(
-        parts.next().unwrap(),
-        parts.next().unwrap(),
-        parts.next().unwrap(),
-    )
+/// Parses an `x,y,z` line into a point.
+pub fn parse_point(line: &str) -> Result<Point3D, ParseError> {
+    let parts: Vec<&str> = line.split(',').map(str::trim).collect();
+    let [x, y, z] = parts[..] else {
+        return Err(ParseError::at_column(
+            1,
+            format!("expected 3 coordinates, found {}", parts.len()),
+        ));
+    };
+    Ok(Point3D(
+        parse_num(line, x)?,
+        parse_num(line, y)?,
+        parse_num(line, z)?,
+    ))
 }
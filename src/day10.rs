@@ -1,4 +1,4 @@
-use aoc::parse_lines;
+use crate::error::{ParseError, SolveError, column_of, parse_num, try_parse_lines};
 use num_rational::Rational32;
 use std::borrow::Cow;
 use std::collections::{HashSet, VecDeque};
@@ -9,26 +9,35 @@ const ONE: Rational32 = Rational32::ONE;
 
 pub struct Solution;
 
-impl aoc::Solution for Solution {
-    fn solve_1(&self, input: String) -> String {
-        parse_lines(&input)
-            .map(|machine: Machine| machine.solve_part_1())
-            .sum::<i32>()
-            .to_string()
+impl crate::Day for Solution {
+    type Input = Vec<Machine>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Machine>, SolveError> {
+        Ok(try_parse_lines(input, str::parse)?)
+    }
+
+    fn solve_1(&self, machines: Vec<Machine>) -> Result<String, SolveError> {
+        Ok(machines
+            .iter()
+            .map(Machine::solve_part_1)
+            .sum::<Result<i32, _>>()?
+            .to_string())
     }
 
-    fn solve_2(&self, input: String) -> String {
-        parse_lines(&input)
-            .map(|machine: Machine| machine.solve_part_2())
-            .sum::<i32>()
-            .to_string()
+    fn solve_2(&self, machines: Vec<Machine>) -> Result<String, SolveError> {
+        Ok(machines
+            .iter()
+            .map(Machine::solve_part_2)
+            .sum::<Result<i32, _>>()?
+            .to_string())
     }
 }
 
-struct Machine {
-    lights_target: Vec<bool>,
-    buttons: Vec<Vec<usize>>,
-    joltage_target: Vec<i32>,
+/// A machine from the manual: its indicator light diagram, button wirings, and joltage requirements.
+pub struct Machine {
+    pub lights_target: Vec<bool>,
+    pub buttons: Vec<Vec<usize>>,
+    pub joltage_target: Vec<i32>,
 }
 
 impl Machine {
@@ -48,28 +57,32 @@ impl Machine {
         new_joltage
     }
 
-    fn solve_part_1(&self) -> i32 {
+    /// Finds the fewest button presses that set the indicator lights to the target pattern.
+    pub fn solve_part_1(&self) -> Result<i32, SolveError> {
         let mut q = VecDeque::new();
         q.push_back((vec![false; self.lights_target.len()], 0));
         let mut seen = HashSet::new();
-        loop {
-            let (lights, presses) = q.pop_front().unwrap();
+        while let Some((lights, presses)) = q.pop_front() {
             if !seen.insert(lights.clone()) {
                 continue;
             }
             if lights == *self.lights_target {
-                return presses;
+                return Ok(presses);
             }
             for button_idx in 0..self.buttons.len() {
                 let new_lights = self.press_button_lights(button_idx, &lights);
                 q.push_back((new_lights, presses + 1));
             }
         }
+        Err(SolveError::Unsolvable(
+            "no button presses reach the target lights".into(),
+        ))
     }
 
     #[allow(clippy::needless_range_loop)] // more clear with indices
     #[allow(clippy::too_many_lines)] // dude I know but I aint gonna fix it
-    fn solve_part_2(&self) -> i32 {
+    /// Finds the fewest button presses that bring the joltage counters to their targets.
+    pub fn solve_part_2(&self) -> Result<i32, SolveError> {
         log::info!(
             "starting solve_part_2 for joltage target {:?}",
             self.joltage_target
@@ -110,15 +123,16 @@ impl Machine {
         let presses: Vec<i32> = if free_vars.is_empty() {
             log::info!("Unique solution found");
             m.iter()
-                .map(|row| try_into_i32(row[self.buttons.len()]).unwrap())
-                .collect()
+                .map(|row| try_into_i32(row[self.buttons.len()]))
+                .collect::<Result<_, _>>()
+                .map_err(SolveError::Unsolvable)?
         } else {
             log::info!("Free variables found at columns {free_vars:?}");
-            assert_eq!(
-                free_vars.len() + m.len(),
-                self.buttons.len(),
-                "not enough free variables to solve the system"
-            );
+            if free_vars.len() + m.len() != self.buttons.len() {
+                return Err(SolveError::Unsolvable(
+                    "not enough free variables to solve the system".into(),
+                ));
+            }
 
             let mut var_ranges = vec![];
             for var in free_vars {
@@ -138,7 +152,9 @@ impl Machine {
                     }
                     let last_col = row[self.buttons.len()];
                     if last_col > ZERO && v > ZERO {
-                        max_val = max_val.min(try_into_i32((last_col / v).floor()).unwrap());
+                        let bound =
+                            try_into_i32((last_col / v).floor()).map_err(SolveError::Unsolvable)?;
+                        max_val = max_val.min(bound);
                     }
                 }
                 if max_val == i32::MAX {
@@ -194,7 +210,9 @@ impl Machine {
                 }
             }
 
-            best_solution.expect("did not find any valid solution")
+            best_solution.ok_or(SolveError::Unsolvable(
+                "did not find any valid solution".into(),
+            ))?
         };
 
         // simulate presses to verify
@@ -202,15 +220,19 @@ impl Machine {
         for (b, &n) in presses.iter().enumerate() {
             joltage = self.press_button_joltage(b, n, &joltage);
         }
-        assert_eq!(
-            joltage, self.joltage_target,
-            "simulated joltage {:?} does not match target {:?}",
-            joltage, self.joltage_target
-        );
+        if joltage != self.joltage_target {
+            return Err(SolveError::Unsolvable(
+                format!(
+                    "simulated joltage {:?} does not match target {:?}",
+                    joltage, self.joltage_target
+                )
+                .into(),
+            ));
+        }
 
         let total = presses.into_iter().sum();
         log::info!("finished solve_part_2 with presses {total}");
-        total
+        Ok(total)
     }
 }
 
@@ -302,20 +324,25 @@ fn try_into_i32(f: Rational32) -> Result<i32, Cow<'static, str>> {
 }
 
 impl FromStr for Machine {
-    type Err = Cow<'static, str>;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // [#.###] (0,1) (0,2,3,4) (0,1,4) (3,4) {37,29,8,20,35}
         let parts: Vec<_> = s.split_whitespace().collect();
 
-        let lights_str = parts.first().ok_or("missing lights")?;
+        let lights_str = parts
+            .first()
+            .ok_or(ParseError::at_column(1, "missing lights"))?;
         let lights_target: Vec<bool> = lights_str
             .trim_matches(&['[', ']'][..])
-            .chars()
-            .map(|c| match c {
+            .char_indices()
+            .map(|(i, c)| match c {
                 '#' => Ok(true),
                 '.' => Ok(false),
-                _ => Err(format!("invalid light character {c}")),
+                _ => Err(ParseError::at_column(
+                    column_of(s, lights_str) + i + 1,
+                    format!("invalid light character {c}"),
+                )),
             })
             .collect::<Result<_, _>>()?;
 
@@ -329,24 +356,41 @@ impl FromStr for Machine {
                     .trim_matches(&['(', ')'][..])
                     .split(',')
                     .map(|num_str| {
-                        num_str
-                            .parse::<usize>()
-                            .map_err(|e| format!("invalid button index: {e:?}"))
+                        let idx: usize = parse_num(s, num_str)?;
+                        if idx >= lights_target.len() {
+                            return Err(ParseError::at_column(
+                                column_of(s, num_str),
+                                format!(
+                                    "button index {idx} is out of range for {} lights",
+                                    lights_target.len()
+                                ),
+                            ));
+                        }
+                        Ok(idx)
                     })
                     .collect::<Result<_, _>>()
             })
             .collect::<Result<_, _>>()?;
 
-        let joltage_str = parts.last().ok_or("missing joltage")?;
+        let joltage_str = parts
+            .last()
+            .filter(|_| parts.len() > 1)
+            .ok_or(ParseError::at_column(s.len() + 1, "missing joltage"))?;
         let joltage_target: Vec<i32> = joltage_str
             .trim_matches(&['{', '}'][..])
             .split(',')
-            .map(|num_str| {
-                num_str
-                    .parse::<i32>()
-                    .map_err(|e| format!("invalid joltage value: {e:?}"))
-            })
+            .map(|num_str| parse_num(s, num_str))
             .collect::<Result<_, _>>()?;
+        if joltage_target.len() != lights_target.len() {
+            return Err(ParseError::at_column(
+                column_of(s, joltage_str),
+                format!(
+                    "expected {} joltage values, found {}",
+                    lights_target.len(),
+                    joltage_target.len()
+                ),
+            ));
+        }
 
         Ok(Machine {
             lights_target,
@@ -1,11 +1,17 @@
-use aoc::{Counter, parse_lines_with};
+use crate::error::{ParseError, SolveError, try_parse_lines};
+use aoc::Counter;
 use std::collections::{HashMap, HashSet, VecDeque};
 
 pub struct Solution;
 
-impl aoc::Solution for Solution {
-    fn solve_1(&self, input: String) -> String {
-        let graph: HashMap<String, Vec<String>> = parse_lines_with(&input, parse_line).collect();
+impl crate::Day for Solution {
+    type Input = HashMap<String, Vec<String>>;
+
+    fn parse(&self, input: &str) -> Result<HashMap<String, Vec<String>>, SolveError> {
+        Ok(try_parse_lines(input, parse_line)?.into_iter().collect())
+    }
+
+    fn solve_1(&self, graph: HashMap<String, Vec<String>>) -> Result<String, SolveError> {
         let mut q = vec![("you".to_string(), 1)];
         let mut counts = Counter::new();
         while let Some((node, n)) = q.pop() {
@@ -16,48 +22,64 @@ impl aoc::Solution for Solution {
                 }
             }
         }
-        counts.get("out").to_string()
+        Ok(counts.get("out").to_string())
     }
 
-    fn solve_2(&self, input: String) -> String {
-        let graph: HashMap<String, Vec<String>> = parse_lines_with(&input, parse_line).collect();
-        let mut q = vec!["svr".to_string()];
-        let mut incoming: HashMap<String, HashSet<String>> = HashMap::new();
-        incoming.insert("svr".to_string(), HashSet::new());
+    fn solve_2(&self, graph: HashMap<String, Vec<String>>) -> Result<String, SolveError> {
+        let incoming = incoming_edges(&graph, "svr")?;
 
-        while let Some(node) = q.pop() {
-            if node == "out" {
-                continue;
-            }
-            for child in &graph[&node] {
-                let child_incoming = incoming.entry(child.clone()).or_default();
-                if !child_incoming.insert(node.clone()) {
-                    continue;
-                }
-                q.push(child.clone());
-            }
-        }
+        let svr_to_dac = paths(&graph, &incoming, "svr", "dac")?;
+        let svr_to_fft = paths(&graph, &incoming, "svr", "fft")?;
+        let dac_to_fft = paths(&graph, &incoming, "dac", "fft")?;
+        let fft_to_dac = paths(&graph, &incoming, "fft", "dac")?;
+        let fft_to_out = paths(&graph, &incoming, "fft", "out")?;
+        let dac_to_out = paths(&graph, &incoming, "dac", "out")?;
 
-        log::info!("Finished building incoming graph");
+        Ok(
+            ((svr_to_dac * dac_to_fft * fft_to_out) + (svr_to_fft * fft_to_dac * dac_to_out))
+                .to_string(),
+        )
+    }
+}
 
-        let svr_to_dac = paths(&graph, &incoming, "svr", "dac");
-        let svr_to_fft = paths(&graph, &incoming, "svr", "fft");
-        let dac_to_fft = paths(&graph, &incoming, "dac", "fft");
-        let fft_to_dac = paths(&graph, &incoming, "fft", "dac");
-        let fft_to_out = paths(&graph, &incoming, "fft", "out");
-        let dac_to_out = paths(&graph, &incoming, "dac", "out");
+/// Builds the reverse adjacency of every device reachable from `root`.
+#[allow(clippy::implicit_hasher)]
+pub fn incoming_edges(
+    graph: &HashMap<String, Vec<String>>,
+    root: &str,
+) -> Result<HashMap<String, HashSet<String>>, SolveError> {
+    let mut q = vec![root.to_string()];
+    let mut incoming: HashMap<String, HashSet<String>> = HashMap::new();
+    incoming.insert(root.to_string(), HashSet::new());
 
-        ((svr_to_dac * dac_to_fft * fft_to_out) + (svr_to_fft * fft_to_dac * dac_to_out))
-            .to_string()
+    while let Some(node) = q.pop() {
+        if node == "out" {
+            continue;
+        }
+        let children = graph.get(&node).ok_or_else(|| {
+            SolveError::Unsolvable(format!("device '{node}' has no outputs listed").into())
+        })?;
+        for child in children {
+            let child_incoming = incoming.entry(child.clone()).or_default();
+            if !child_incoming.insert(node.clone()) {
+                continue;
+            }
+            q.push(child.clone());
+        }
     }
+
+    log::info!("Finished building incoming graph");
+    Ok(incoming)
 }
 
-fn paths(
+/// Counts the distinct paths from `start` to `end`, using `incoming` from [`incoming_edges`].
+#[allow(clippy::implicit_hasher)]
+pub fn paths(
     graph: &HashMap<String, Vec<String>>,
     incoming: &HashMap<String, HashSet<String>>,
     start: &str,
     end: &str,
-) -> u64 {
+) -> Result<u64, SolveError> {
     log::info!("Calculating paths from {start} to {end}");
     let mut queue = VecDeque::new();
     queue.push_back(start.to_string());
@@ -115,13 +137,18 @@ fn paths(
         }
     }
 
-    let paths = *paths.get(end).unwrap();
+    let paths = *paths.get(end).ok_or_else(|| {
+        SolveError::Unsolvable(format!("device '{end}' is not connected to '{start}'").into())
+    })?;
     log::info!("Total paths from {start} to {end}: {paths}");
-    paths
+    Ok(paths)
 }
 
-fn parse_line(line: &str) -> (String, Vec<String>) {
-    let (name, outputs) = line.split_once(": ").unwrap();
+/// Parses a `name: output output ...` line.
+pub fn parse_line(line: &str) -> Result<(String, Vec<String>), ParseError> {
+    let (name, outputs) = line.split_once(": ").ok_or_else(|| {
+        ParseError::at_column(1, format!("expected 'name: outputs', found '{line}'"))
+    })?;
     let outputs = outputs.split_ascii_whitespace().map(String::from).collect();
-    (name.to_string(), outputs)
+    Ok((name.to_string(), outputs))
 }
@@ -1,19 +1,24 @@
-use aoc::{Grid, parse_lines};
-use std::borrow::Cow;
+use crate::error::{ParseError, SolveError, parse_grid, parse_num, sections, try_parse_lines};
+use aoc::Grid;
 use std::str::FromStr;
 
 pub struct Solution;
 
-impl aoc::Solution for Solution {
-    fn solve_1(&self, input: String) -> String {
-        let (shapes, areas) = parse_input(&input);
+impl crate::Day for Solution {
+    type Input = (Vec<Grid<char>>, Vec<Area>);
+
+    fn parse(&self, input: &str) -> Result<(Vec<Grid<char>>, Vec<Area>), SolveError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn solve_1(&self, (shapes, areas): (Vec<Grid<char>>, Vec<Area>)) -> Result<String, SolveError> {
         let cells_per_shape: Vec<usize> = shapes
             .iter()
             .map(|shape| shape.points_with_item().filter(|(_, c)| **c == '#').count())
             .collect();
-        areas
+        let fits = areas
             .iter()
-            .filter(|area| {
+            .map(|area| {
                 let can_fit_no_packing = {
                     let w3 = area.width / 3;
                     let h3 = area.height / 3;
@@ -22,7 +27,7 @@ impl aoc::Solution for Solution {
                     total_needed <= area_size
                 };
                 if can_fit_no_packing {
-                    return true;
+                    return Ok(true);
                 }
                 let cant_fit_optimal_packing = {
                     let total_area = area.width * area.height;
@@ -35,66 +40,81 @@ impl aoc::Solution for Solution {
                     total_needed > total_area
                 };
                 if cant_fit_optimal_packing {
-                    return false;
+                    return Ok(false);
                 }
-                panic!("Area requires packing logic which is not implemented");
+                Err(SolveError::Unsolvable(
+                    "Area requires packing logic which is not implemented".into(),
+                ))
             })
-            .count()
-            .to_string()
+            .collect::<Result<Vec<bool>, _>>()?;
+        Ok(fits.into_iter().filter(|&it| it).count().to_string())
     }
 
-    fn solve_2(&self, _input: String) -> String {
-        "Merry ~~Christmas~~ 12th!".to_string()
+    fn solve_2(&self, _input: (Vec<Grid<char>>, Vec<Area>)) -> Result<String, SolveError> {
+        Ok("Merry ~~Christmas~~ 12th!".to_string())
     }
 }
 
-fn parse_input(input: &str) -> (Vec<Grid<char>>, Vec<Area>) {
-    let sections: Vec<_> = input.split("\n\n").collect();
-    let shapes = sections[..sections.len() - 1]
+/// Parses the present shapes and the regions under the trees.
+pub fn parse_input(input: &str) -> Result<(Vec<Grid<char>>, Vec<Area>), ParseError> {
+    let sections = sections(input);
+    let ((areas_offset, areas_str), shape_sections) = sections
+        .split_last()
+        .ok_or(ParseError::at_column(1, "empty input"))?;
+    let shapes: Vec<Grid<char>> = shape_sections
         .iter()
-        .map(|shape_str| {
-            let (_, rest) = shape_str.trim().split_once('\n').unwrap();
-            rest.parse().unwrap()
+        .map(|&(offset, shape_str)| {
+            let (_, rest) = shape_str
+                .trim()
+                .split_once('\n')
+                .ok_or_else(|| ParseError::new(offset + 2, 1, "missing shape below its index"))?;
+            parse_grid(rest, Some).map_err(|e| e.offset(offset + 1))
         })
-        .collect();
-    let areas = parse_lines(sections[sections.len() - 1]).collect();
-    (shapes, areas)
+        .collect::<Result<_, _>>()?;
+    let areas: Vec<Area> = try_parse_lines(areas_str, |line| {
+        let area: Area = line.parse()?;
+        if area.shape_counts.len() > shapes.len() {
+            return Err(ParseError::at_column(
+                1,
+                format!(
+                    "area lists {} shape counts but there are only {} shapes",
+                    area.shape_counts.len(),
+                    shapes.len()
+                ),
+            ));
+        }
+        Ok(area)
+    })
+    .map_err(|e| e.offset(*areas_offset))?;
+    Ok((shapes, areas))
 }
 
-struct Area {
-    width: usize,
-    height: usize,
-    shape_counts: Vec<usize>,
+/// A region under a tree and how many of each present shape must fit in it.
+pub struct Area {
+    pub width: usize,
+    pub height: usize,
+    pub shape_counts: Vec<usize>,
 }
 
 impl FromStr for Area {
-    type Err = Cow<'static, str>;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // 39x39: 45 41 45 32 31 42
 
-        let (dim_str, counts_str) = s
-            .split_once(':')
-            .ok_or("missing ':' separating dimensions and counts")?;
+        let (dim_str, counts_str) = s.split_once(':').ok_or(ParseError::at_column(
+            1,
+            "missing ':' separating dimensions and counts",
+        ))?;
         let (width_str, height_str) = dim_str
             .trim()
             .split_once('x')
-            .ok_or("missing 'x' in dimensions")?;
-        let width = width_str
-            .trim()
-            .parse()
-            .map_err(|e| format!("failed to parse width: {e:?}"))?;
-        let height = height_str
-            .trim()
-            .parse()
-            .map_err(|e| format!("failed to parse height: {e:?}"))?;
+            .ok_or(ParseError::at_column(1, "missing 'x' in dimensions"))?;
+        let width = parse_num(s, width_str.trim())?;
+        let height = parse_num(s, height_str.trim())?;
         let shape_counts = counts_str
             .split_whitespace()
-            .map(|count_str| {
-                count_str
-                    .parse()
-                    .map_err(|e| format!("failed to parse shape count: {e:?}"))
-            })
+            .map(|count_str| parse_num(s, count_str))
             .collect::<Result<_, _>>()?;
         Ok(Area {
             width,
@@ -0,0 +1,64 @@
+/// A partition of the elements `0..n` into disjoint sets, which can be merged. Lookups compress
+/// paths and merges hang the smaller set under the larger, so both are close to constant time.
+#[derive(Debug, Clone)]
+pub struct DisjointSets {
+    parent: Vec<usize>,
+    /// The size of each set, kept up to date for representatives only.
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl DisjointSets {
+    /// Puts each of `0..n` in a set of its own.
+    #[must_use]
+    pub fn new(n: usize) -> Self {
+        DisjointSets {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+        }
+    }
+
+    /// Finds the representative of the set holding `i`.
+    pub fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // point everything on the way straight at the root
+        let mut i = i;
+        while self.parent[i] != root {
+            i = std::mem::replace(&mut self.parent[i], root);
+        }
+        root
+    }
+
+    /// Merges the sets holding `a` and `b`, returning `false` if they were already the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) = if self.size[a] < self.size[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        self.components -= 1;
+        true
+    }
+
+    /// How many elements are in the set holding `i`.
+    pub fn size_of(&mut self, i: usize) -> usize {
+        let root = self.find(i);
+        self.size[root]
+    }
+
+    /// How many separate sets there are.
+    #[must_use]
+    pub fn components(&self) -> usize {
+        self.components
+    }
+}
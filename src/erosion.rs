@@ -0,0 +1,132 @@
+use aoc::{Grid, Point};
+use std::collections::HashMap;
+
+/// Which cells around a cell count as its neighbours.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// The four orthogonally adjacent cells.
+    VonNeumann,
+    /// The eight surrounding cells, diagonals included.
+    Moore,
+    /// Any set of `(dx, dy)` offsets from the cell.
+    Custom(Vec<(isize, isize)>),
+}
+
+impl Neighbourhood {
+    #[must_use]
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        match self {
+            Neighbourhood::VonNeumann => vec![(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Neighbourhood::Moore => vec![
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+            Neighbourhood::Custom(offsets) => offsets.clone(),
+        }
+    }
+}
+
+/// Decides which cells of a grid erode: a cell goes once it's `solid` and fewer than `threshold`
+/// of its neighbours are.
+#[derive(Debug, Clone)]
+pub struct ErosionRule<T> {
+    pub neighbourhood: Neighbourhood,
+    pub threshold: usize,
+    pub solid: fn(&T) -> bool,
+}
+
+/// One round of erosion: every cell that could erode when the round started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wave {
+    /// The cells eroded this round, top to bottom and left to right.
+    pub removed: Vec<Point>,
+    /// How many solid cells are left afterwards.
+    pub remaining: usize,
+}
+
+impl<T> ErosionRule<T> {
+    /// Finds every cell that would erode in the first round.
+    #[must_use]
+    pub fn find_eroding(&self, grid: &Grid<T>) -> Vec<Point> {
+        let offsets = self.neighbourhood.offsets();
+        grid.points_with_item()
+            .filter(|&(p, t)| {
+                (self.solid)(t) && self.count_solid(grid, &offsets, p) < self.threshold
+            })
+            .map(|(p, _)| p)
+            .collect()
+    }
+
+    /// Erodes the grid round by round until it's stable, replacing eroded cells with `cleared`
+    /// and returning what each round removed.
+    ///
+    /// Rather than rescanning the grid after each round, this keeps a count of every solid cell's
+    /// solid neighbours and only revisits cells whose count drops when a neighbour erodes.
+    pub fn erode(&self, grid: &mut Grid<T>, cleared: &T) -> Vec<Wave>
+    where
+        T: Clone,
+    {
+        let offsets = self.neighbourhood.offsets();
+        let mut neighbours: HashMap<Point, usize> = grid
+            .points_with_item()
+            .filter(|(_, t)| (self.solid)(t))
+            .map(|(p, _)| (p, self.count_solid(grid, &offsets, p)))
+            .collect();
+        let mut wave: Vec<Point> = neighbours
+            .iter()
+            .filter(|&(_, &n)| n < self.threshold)
+            .map(|(&p, _)| p)
+            .collect();
+
+        let mut waves = vec![];
+        while !wave.is_empty() {
+            // a cell joins the next wave when its count first drops below the threshold, so it's
+            // only queued once
+            let mut next = vec![];
+            for &p in &wave {
+                grid.set(p, cleared.clone());
+                neighbours.remove(&p);
+                // the cells that count this one as a neighbour
+                for &(dx, dy) in &offsets {
+                    let Some(q) = offset(p, -dx, -dy) else {
+                        continue;
+                    };
+                    if let Some(n) = neighbours.get_mut(&q) {
+                        *n -= 1;
+                        if *n + 1 == self.threshold {
+                            next.push(q);
+                        }
+                    }
+                }
+            }
+            wave.sort_unstable_by_key(|p| (p.1, p.0));
+            waves.push(Wave {
+                removed: wave,
+                remaining: neighbours.len(),
+            });
+            wave = next;
+        }
+        waves
+    }
+
+    fn count_solid(&self, grid: &Grid<T>, offsets: &[(isize, isize)], p: Point) -> usize {
+        offsets
+            .iter()
+            .filter_map(|&(dx, dy)| grid.get(offset(p, dx, dy)?))
+            .filter(|t| (self.solid)(t))
+            .count()
+    }
+}
+
+fn offset(p: Point, dx: isize, dy: isize) -> Option<Point> {
+    Some(Point(
+        p.0.checked_add_signed(dx)?,
+        p.1.checked_add_signed(dy)?,
+    ))
+}
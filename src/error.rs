@@ -0,0 +1,159 @@
+use aoc::Grid;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+/// Bad puzzle input, located by 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: Cow<'static, str>,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// An error on the first line of whatever the parser was given, usually a single line.
+    pub fn at_column(column: usize, message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(1, column, message)
+    }
+
+    /// Shifts the error down by `lines`, for parsers that only saw part of the input.
+    #[must_use]
+    pub fn offset(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    Parse(ParseError),
+    /// The input parsed, but the solution can't produce an answer for it.
+    Unsolvable(Cow<'static, str>),
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::Parse(e) => write!(f, "invalid input at {e}"),
+            SolveError::Unsolvable(msg) => write!(f, "unsolvable input: {msg}"),
+        }
+    }
+}
+
+impl Error for SolveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SolveError::Parse(e) => Some(e),
+            SolveError::Unsolvable(_) => None,
+        }
+    }
+}
+
+impl From<ParseError> for SolveError {
+    fn from(e: ParseError) -> Self {
+        SolveError::Parse(e)
+    }
+}
+
+/// Returns the 1-based column where `part` starts. `part` must be a subslice of `line`.
+#[must_use]
+pub fn column_of(line: &str, part: &str) -> usize {
+    part.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+/// Parses `part`, a subslice of `line`, reporting failures at its column.
+pub fn parse_num<T: FromStr>(line: &str, part: &str) -> Result<T, ParseError>
+where
+    T::Err: Display,
+{
+    part.parse().map_err(|e| {
+        ParseError::at_column(
+            column_of(line, part),
+            format!("invalid number '{part}': {e}"),
+        )
+    })
+}
+
+/// Parses every line of `input` with `f`, fixing up the line numbers of any error.
+pub fn try_parse_lines<T>(
+    input: &str,
+    mut f: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| f(line).map_err(|e| e.offset(i)))
+        .collect()
+}
+
+/// Splits `input` on blank lines, pairing each section with the number of lines before it.
+#[must_use]
+pub fn sections(input: &str) -> Vec<(usize, &str)> {
+    let mut offset = 0;
+    input
+        .split("\n\n")
+        .map(|section| {
+            let start = offset;
+            offset += section.lines().count() + 1;
+            (start, section)
+        })
+        .collect()
+}
+
+/// Checks that there is at least one row, every character with `parse_tile` and that the rows
+/// line up before building the grid, so the `From<char>` it's built with only ever sees
+/// characters `parse_tile` accepts.
+pub fn parse_grid<T: From<char>>(
+    input: &str,
+    parse_tile: impl Fn(char) -> Option<T>,
+) -> Result<Grid<T>, ParseError>
+where
+    Grid<T>: FromStr<Err: Debug>,
+{
+    if input.lines().next().is_none() {
+        return Err(ParseError::new(1, 1, "empty grid"));
+    }
+    let mut width = None;
+    for (y, line) in input.lines().enumerate() {
+        if let Some((x, c)) = line.char_indices().find(|&(_, c)| parse_tile(c).is_none()) {
+            return Err(ParseError::new(y + 1, x + 1, format!("unknown tile '{c}'")));
+        }
+        let len = line.chars().count();
+        match width {
+            None => width = Some(len),
+            Some(w) if w != len => {
+                return Err(ParseError::new(
+                    y + 1,
+                    w.min(len) + 1,
+                    format!("expected {w} columns, found {len}"),
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+    input
+        .parse()
+        .map_err(|e| ParseError::at_column(1, format!("{e:?}")))
+}
@@ -0,0 +1,120 @@
+use std::fmt::{Display, Formatter};
+
+/// An operator that folds a list of operands into one value, left to right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Max,
+    Min,
+    /// Writes the operands' digits one after another, so 12 and 34 make 1234.
+    Concat,
+}
+
+impl Op {
+    pub const ALL: [Op; 7] = [
+        Op::Add,
+        Op::Subtract,
+        Op::Multiply,
+        Op::Divide,
+        Op::Max,
+        Op::Min,
+        Op::Concat,
+    ];
+
+    #[must_use]
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Op::Add => "+",
+            Op::Subtract => "-",
+            Op::Multiply => "*",
+            Op::Divide => "/",
+            Op::Max => "max",
+            Op::Min => "min",
+            Op::Concat => "||",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(token: &str) -> Option<Self> {
+        Op::ALL.into_iter().find(|op| op.symbol() == token)
+    }
+
+    /// Folds the operands together. An empty sum is 0 and an empty product 1; the other
+    /// operators need at least one operand.
+    pub fn apply(self, operands: &[u128]) -> Result<u128, EvalError> {
+        let overflow = EvalError::Overflow(self);
+        let Some((&first, rest)) = operands.split_first() else {
+            return match self {
+                Op::Add => Ok(0),
+                Op::Multiply => Ok(1),
+                _ => Err(EvalError::NoOperands(self)),
+            };
+        };
+        rest.iter().try_fold(first, |acc, &n| match self {
+            Op::Add => acc.checked_add(n).ok_or(overflow),
+            Op::Subtract => acc.checked_sub(n).ok_or(EvalError::Negative),
+            Op::Multiply => acc.checked_mul(n).ok_or(overflow),
+            Op::Divide => acc.checked_div(n).ok_or(EvalError::DivisionByZero),
+            Op::Max => Ok(acc.max(n)),
+            Op::Min => Ok(acc.min(n)),
+            Op::Concat => {
+                let digits = n.checked_ilog10().map_or(1, |d| d + 1);
+                10u128
+                    .checked_pow(digits)
+                    .and_then(|shift| acc.checked_mul(shift))
+                    .and_then(|shifted| shifted.checked_add(n))
+                    .ok_or(overflow)
+            }
+        })
+    }
+}
+
+impl Display for Op {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// An operator applied to a list of operands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expression {
+    pub op: Op,
+    pub operands: Vec<u128>,
+}
+
+impl Expression {
+    pub fn evaluate(&self) -> Result<u128, EvalError> {
+        self.op.apply(&self.operands)
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let operands: Vec<String> = self.operands.iter().map(u128::to_string).collect();
+        write!(f, "{}", operands.join(&format!(" {} ", self.op)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalError {
+    Overflow(Op),
+    Negative,
+    DivisionByZero,
+    NoOperands(Op),
+}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalError::Overflow(op) => write!(f, "'{op}' overflows u128"),
+            EvalError::Negative => write!(f, "subtraction goes below zero"),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::NoOperands(op) => write!(f, "'{op}' needs at least one operand"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
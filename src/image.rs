@@ -0,0 +1,21 @@
+use std::io::{self, Write};
+use std::iter::repeat_n;
+
+/// Writes rows of pixels as raw image data, blowing each pixel up into a `scale` by `scale`
+/// block. A pixel is `N` bytes: one for a PGM, three for a PPM.
+pub fn write_scaled_rows<const N: usize>(
+    rows: impl IntoIterator<Item = impl IntoIterator<Item = [u8; N]>>,
+    scale: usize,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    for row in rows {
+        let line: Vec<u8> = row
+            .into_iter()
+            .flat_map(|pixel| repeat_n(pixel, scale).flatten())
+            .collect();
+        for _ in 0..scale {
+            out.write_all(&line)?;
+        }
+    }
+    Ok(())
+}
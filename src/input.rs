@@ -0,0 +1,16 @@
+use std::io;
+use std::path::PathBuf;
+
+/// Directory holding puzzle inputs as `day01.txt` through `day12.txt`, overridable with
+/// `AOC_INPUT_DIR`.
+#[must_use]
+pub fn dir() -> PathBuf {
+    std::env::var_os("AOC_INPUT_DIR").map_or_else(|| PathBuf::from("input"), PathBuf::from)
+}
+
+/// Reads the puzzle input for `day` (1-based) from [`dir`].
+pub fn load(day: usize) -> io::Result<String> {
+    let path = dir().join(format!("day{day:02}.txt"));
+    std::fs::read_to_string(&path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))
+}
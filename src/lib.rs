@@ -0,0 +1,140 @@
+#![warn(clippy::pedantic)]
+#![allow(clippy::missing_errors_doc)] // it's always bad input
+
+use crate::error::SolveError;
+use std::fmt::{Display, Formatter};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+pub mod bench;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod disjoint_set;
+pub mod erosion;
+pub mod error;
+pub mod expression;
+pub mod image;
+pub mod input;
+pub mod range_set;
+pub mod runner;
+pub mod warnings;
+
+/// A day's solution that reports bad input instead of panicking, with parsing kept apart from
+/// solving so the two can be timed separately.
+pub trait Day: Sync {
+    /// The puzzle input once parsed, with everything either part needs.
+    type Input;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, SolveError>;
+    fn solve_1(&self, input: Self::Input) -> Result<String, SolveError>;
+    fn solve_2(&self, input: Self::Input) -> Result<String, SolveError>;
+}
+
+/// Any [`Day`], with its input type hidden so every day can go in one list.
+pub trait Solve: Sync {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError>;
+    /// Parses the input and solves one part of it, returning how long each took.
+    fn time(&self, part: Part, input: &str) -> Result<(Duration, Duration), SolveError>;
+}
+
+impl<D: Day> Solve for D {
+    fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
+        solve_part(self, part, self.parse(input)?)
+    }
+
+    fn time(&self, part: Part, input: &str) -> Result<(Duration, Duration), SolveError> {
+        let start = Instant::now();
+        let input = black_box(self.parse(black_box(input))?);
+        let parse_time = start.elapsed();
+
+        let start = Instant::now();
+        black_box(solve_part(self, part, input)?);
+        Ok((parse_time, start.elapsed()))
+    }
+}
+
+fn solve_part<D: Day>(day: &D, part: Part, input: D::Input) -> Result<String, SolveError> {
+    match part {
+        Part::One => day.solve_1(input),
+        Part::Two => day.solve_2(input),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+pub const DAYS: [&dyn Solve; 12] = [
+    &day01::Solution,
+    &day02::Solution,
+    &day03::Solution,
+    &day04::Solution,
+    &day05::Solution,
+    &day06::Solution,
+    &day07::Solution,
+    &day08::Solution,
+    &day09::Solution,
+    &day10::Solution,
+    &day11::Solution,
+    &day12::Solution,
+];
+
+pub const SOLUTIONS: [&dyn aoc::Solution; 12] = [
+    &Checked(DAYS[0]),
+    &Checked(DAYS[1]),
+    &Checked(DAYS[2]),
+    &Checked(DAYS[3]),
+    &Checked(DAYS[4]),
+    &Checked(DAYS[5]),
+    &Checked(DAYS[6]),
+    &Checked(DAYS[7]),
+    &Checked(DAYS[8]),
+    &Checked(DAYS[9]),
+    &Checked(DAYS[10]),
+    &Checked(DAYS[11]),
+];
+
+/// Runs a [`Solve`] as an [`aoc::Solution`], answering with the error rather than aborting the run.
+pub struct Checked(pub &'static dyn Solve);
+
+impl aoc::Solution for Checked {
+    fn solve_1(&self, input: String) -> String {
+        answer_or_error(self.0.solve(Part::One, &input))
+    }
+
+    fn solve_2(&self, input: String) -> String {
+        answer_or_error(self.0.solve(Part::Two, &input))
+    }
+}
+
+fn answer_or_error(result: Result<String, SolveError>) -> String {
+    result.unwrap_or_else(|e| {
+        log::error!("{e}");
+        format!("error: {e}")
+    })
+}
@@ -1,37 +1,69 @@
 #![warn(clippy::pedantic)]
 
-use aoc::Solution;
+use aoc2025::{DAYS, SOLUTIONS, bench, runner, warnings};
+use std::fs::File;
+use std::io::{BufWriter, Write, stdout};
+use std::process::exit;
+use std::thread::available_parallelism;
+use std::time::Instant;
 
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-
-const SOLUTIONS: [&dyn Solution; 12] = [
-    &day01::Solution,
-    &day02::Solution,
-    &day03::Solution,
-    &day04::Solution,
-    &day05::Solution,
-    &day06::Solution,
-    &day07::Solution,
-    &day08::Solution,
-    &day09::Solution,
-    &day10::Solution,
-    &day11::Solution,
-    &day12::Solution,
-];
+const BENCH_OUTPUT: &str = "bench_output.txt";
 
 fn main() {
-    aoc::run("2025", SOLUTIONS);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        // aoc2025 bench [ITERATIONS]
+        Some("bench") => run_bench(args.get(1).map(String::as_str)),
+        // aoc2025 parallel [THREADS]
+        Some("parallel") => run_parallel(args.get(1).map(String::as_str), false),
+        // aoc2025 json [THREADS]
+        Some("json") => run_parallel(args.get(1).map(String::as_str), true),
+        _ => aoc::run("2025", SOLUTIONS),
+    }
+}
+
+fn parse_count(arg: Option<&str>, default: usize, what: &str) -> usize {
+    match arg.map_or(Ok(default), str::parse) {
+        Ok(n) => n,
+        Err(e) => {
+            eprintln!("invalid {what}: {e}");
+            exit(2);
+        }
+    }
+}
+
+fn run_bench(iterations: Option<&str>) {
+    let iterations = parse_count(iterations, 10, "iteration count");
+    let result = File::create(BENCH_OUTPUT).and_then(|file| {
+        let mut out = BufWriter::new(file);
+        bench::run(&DAYS, iterations, &mut out)?;
+        out.flush()
+    });
+    if let Err(e) = result {
+        eprintln!("failed to write {BENCH_OUTPUT}: {e}");
+        exit(1);
+    }
+}
+
+fn run_parallel(threads: Option<&str>, json: bool) {
+    let default_threads = available_parallelism().map_or(1, usize::from);
+    let threads = parse_count(threads, default_threads, "thread count");
+    if let Err(e) = warnings::install() {
+        eprintln!("failed to install warning collector: {e}");
+    }
+    let start = Instant::now();
+    let outcomes = runner::run_parallel(&DAYS, threads);
+    if json {
+        if let Err(e) = runner::write_json_lines(&outcomes, &mut stdout().lock()) {
+            eprintln!("failed to write results: {e}");
+            exit(1);
+        }
+    } else {
+        runner::print_report(&outcomes, start.elapsed());
+    }
+    if outcomes.iter().any(|o| o.result.is_err()) {
+        exit(1);
+    }
 }
 
 #[cfg(test)]
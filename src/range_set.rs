@@ -0,0 +1,152 @@
+/// An integer type that can bound the ranges in a [`RangeSet`]. Ranges may run right up to the
+/// type's maximum, so stepping past either end gives `None` rather than overflowing.
+pub trait Bound: Copy + Ord {
+    fn successor(self) -> Option<Self>;
+    fn predecessor(self) -> Option<Self>;
+    /// How many values `start..=end` holds.
+    fn count(start: Self, end: Self) -> u128;
+}
+
+macro_rules! impl_bound {
+    ($($t:ty),*) => {
+        $(impl Bound for $t {
+            fn successor(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+
+            fn predecessor(self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+
+            fn count(start: Self, end: Self) -> u128 {
+                u128::try_from(end - start).expect("no integer type here is wider than u128") + 1
+            }
+        })*
+    };
+}
+
+impl_bound!(u8, u16, u32, u64, usize);
+
+/// A set of integers stored as sorted, inclusive `(start, end)` ranges, with any that overlap or
+/// touch merged together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: Bound> Default for RangeSet<T> {
+    fn default() -> Self {
+        RangeSet { ranges: vec![] }
+    }
+}
+
+impl<T: Bound> FromIterator<(T, T)> for RangeSet<T> {
+    /// Builds the set from inclusive ranges in any order. Ranges with `start > end` are empty.
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        let mut ranges: Vec<(T, T)> = iter.into_iter().filter(|(s, e)| s <= e).collect();
+        ranges.sort_unstable_by_key(|&(s, _)| s);
+        let ranges = ranges
+            .into_iter()
+            .fold(vec![], |mut acc: Vec<(T, T)>, (start, end)| {
+                match acc.last_mut() {
+                    Some((_, last_end))
+                        if last_end.successor().is_none_or(|next| start <= next) =>
+                    {
+                        *last_end = (*last_end).max(end);
+                    }
+                    _ => acc.push((start, end)),
+                }
+                acc
+            });
+        RangeSet { ranges }
+    }
+}
+
+impl<T: Bound> RangeSet<T> {
+    /// The merged ranges, in order.
+    #[must_use]
+    pub fn ranges(&self) -> &[(T, T)] {
+        &self.ranges
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Checks whether `value` is in any range, by binary search.
+    #[must_use]
+    pub fn contains(&self, value: T) -> bool {
+        self.position(value).is_some()
+    }
+
+    /// Finds which of the [`ranges`](Self::ranges) holds `value`, by binary search.
+    #[must_use]
+    pub fn position(&self, value: T) -> Option<usize> {
+        let after = self.ranges.partition_point(|&(s, _)| s <= value);
+        Some(after.checked_sub(1)?).filter(|&i| self.ranges[i].1 >= value)
+    }
+
+    /// How many values the set holds, which can be one more than the bound type's maximum.
+    #[must_use]
+    pub fn len(&self) -> u128 {
+        self.ranges.iter().map(|&(s, e)| T::count(s, e)).sum()
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        self.ranges.iter().chain(&other.ranges).copied().collect()
+    }
+
+    #[must_use]
+    pub fn intersection(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = vec![];
+        while let (Some(&(a_start, a_end)), Some(&(b_start, b_end))) =
+            (self.ranges.get(i), other.ranges.get(j))
+        {
+            let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+            if start <= end {
+                ranges.push((start, end));
+            }
+            // whichever ends first can't overlap anything further along the other
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        RangeSet { ranges }
+    }
+
+    /// The values in this set that aren't in `other`.
+    #[must_use]
+    pub fn difference(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut j = 0;
+        let mut ranges = vec![];
+        for &(start, end) in &self.ranges {
+            // skip the ranges of `other` that end before this one starts
+            while other.ranges.get(j).is_some_and(|&(_, e)| e < start) {
+                j += 1;
+            }
+            let mut from = start;
+            let mut exhausted = false;
+            for &(cut_start, cut_end) in other.ranges[j..].iter().take_while(|(s, _)| *s <= end) {
+                if let Some(before) = cut_start.predecessor().filter(|&b| b >= from) {
+                    ranges.push((from, before));
+                }
+                match cut_end.successor() {
+                    Some(after) if cut_end < end => from = after,
+                    _ => {
+                        exhausted = true;
+                        break;
+                    }
+                }
+            }
+            if !exhausted {
+                ranges.push((from, end));
+            }
+        }
+        RangeSet { ranges }
+    }
+}
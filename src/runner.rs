@@ -0,0 +1,179 @@
+use crate::error::SolveError;
+use crate::{Part, Solve};
+use std::any::Any;
+use std::fmt::{Display, Formatter, Write as _};
+use std::io::{self, Write};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How one part of one day went.
+pub struct Outcome {
+    pub day: usize,
+    pub part: Part,
+    pub result: Result<String, Failure>,
+    pub duration: Duration,
+    /// Warnings logged while solving, if [`crate::warnings::install`] was called.
+    pub warnings: Vec<String>,
+}
+
+pub enum Failure {
+    /// The puzzle input couldn't be read.
+    Input(String),
+    Solve(SolveError),
+    Panic(String),
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Failure::Input(msg) => write!(f, "missing input: {msg}"),
+            Failure::Solve(e) => write!(f, "{e}"),
+            Failure::Panic(msg) => write!(f, "panicked: {msg}"),
+        }
+    }
+}
+
+/// Runs both parts of every day across `threads` worker threads, catching panics so one
+/// broken day doesn't take the rest down. Outcomes are returned in day and part order.
+///
+/// # Panics
+///
+/// If a worker thread panics outside a solve, which would be a bug in the runner itself.
+#[must_use]
+pub fn run_parallel(days: &[&dyn Solve], threads: usize) -> Vec<Outcome> {
+    let inputs: Vec<_> = (1..=days.len())
+        .map(|day| crate::input::load(day).map_err(|e| e.to_string()))
+        .collect();
+    let jobs: Vec<(usize, Part)> = (1..=days.len())
+        .flat_map(|day| Part::BOTH.map(|part| (day, part)))
+        .collect();
+    let next_job = AtomicUsize::new(0);
+
+    let mut outcomes: Vec<Outcome> = thread::scope(|s| {
+        let workers: Vec<_> = (0..threads.max(1))
+            .map(|_| {
+                s.spawn(|| {
+                    let mut outcomes = vec![];
+                    while let Some(&(day, part)) =
+                        jobs.get(next_job.fetch_add(1, Ordering::Relaxed))
+                    {
+                        outcomes.push(run_part(days[day - 1], day, part, &inputs[day - 1]));
+                    }
+                    outcomes
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().expect("worker panicked outside a solve"))
+            .collect()
+    });
+    outcomes.sort_by_key(|o| (o.day, o.part));
+    outcomes
+}
+
+fn run_part(
+    solution: &dyn Solve,
+    day: usize,
+    part: Part,
+    input: &Result<String, String>,
+) -> Outcome {
+    let start = Instant::now();
+    let (result, warnings) = crate::warnings::capture(|| match input {
+        Ok(input) => match catch_unwind(AssertUnwindSafe(|| solution.solve(part, input))) {
+            Ok(Ok(answer)) => Ok(answer),
+            Ok(Err(e)) => Err(Failure::Solve(e)),
+            Err(payload) => Err(Failure::Panic(panic_message(payload.as_ref()))),
+        },
+        Err(e) => Err(Failure::Input(e.clone())),
+    });
+    Outcome {
+        day,
+        part,
+        result,
+        duration: start.elapsed(),
+        warnings,
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        (*msg).to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Prints a table of answers, timings and failures, followed by a one-line summary.
+pub fn print_report(outcomes: &[Outcome], wall_time: Duration) {
+    println!("{:>3}  {:>4}  {:>12}  answer", "day", "part", "time");
+    for o in outcomes {
+        let answer = match &o.result {
+            Ok(answer) => answer.clone(),
+            Err(failure) => format!("FAILED: {failure}"),
+        };
+        println!(
+            "{:>3}  {:>4}  {:>12}  {answer}",
+            o.day,
+            o.part,
+            format!("{:.2?}", o.duration)
+        );
+        for warning in &o.warnings {
+            println!("{:>23}  warning: {warning}", "");
+        }
+    }
+    let failed = outcomes.iter().filter(|o| o.result.is_err()).count();
+    let cpu_time: Duration = outcomes.iter().map(|o| o.duration).sum();
+    println!(
+        "{} parts, {} failed, {wall_time:.2?} wall time, {cpu_time:.2?} total solve time",
+        outcomes.len(),
+        failed
+    );
+}
+
+/// Writes one JSON object per outcome, one per line.
+pub fn write_json_lines(outcomes: &[Outcome], out: &mut impl Write) -> io::Result<()> {
+    for o in outcomes {
+        let status = match &o.result {
+            Ok(answer) => format!(r#""status":"ok","answer":{}"#, json_string(answer)),
+            Err(failure) => format!(
+                r#""status":"failed","error":{}"#,
+                json_string(&failure.to_string())
+            ),
+        };
+        let warnings: Vec<String> = o.warnings.iter().map(|w| json_string(w)).collect();
+        writeln!(
+            out,
+            r#"{{"day":{},"part":{},{status},"duration_ns":{},"warnings":[{}]}}"#,
+            o.day,
+            o.part,
+            o.duration.as_nanos(),
+            warnings.join(",")
+        )?;
+    }
+    Ok(())
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
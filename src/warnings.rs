@@ -0,0 +1,44 @@
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use std::cell::RefCell;
+
+thread_local! {
+    static CAPTURED: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Logger that hands warnings and errors to whichever [`capture`] is running on the same thread.
+struct WarningCollector;
+
+impl Log for WarningCollector {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Warn
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        CAPTURED.with_borrow_mut(|captured| {
+            if let Some(captured) = captured {
+                captured.push(record.args().to_string());
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the collecting logger. Anything logged below warning level is dropped.
+pub fn install() -> Result<(), SetLoggerError> {
+    log::set_logger(&WarningCollector)?;
+    log::set_max_level(LevelFilter::Warn);
+    Ok(())
+}
+
+/// Runs `f`, returning its result along with any warnings it logged on this thread. Nothing is
+/// captured unless [`install`] has been called.
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, Vec<String>) {
+    let outer = CAPTURED.replace(Some(vec![]));
+    let result = f();
+    let warnings = CAPTURED.replace(outer).unwrap_or_default();
+    (result, warnings)
+}
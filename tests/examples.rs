@@ -0,0 +1,693 @@
+use aoc2025::{DAYS, Part};
+
+/// Runs both parts of `day` on `input`. An expected answer of `None` skips that part, and a
+/// part that fails is compared by its error message.
+fn check(
+    day: usize,
+    input: &str,
+    part_1: impl Into<Option<&'static str>>,
+    part_2: impl Into<Option<&'static str>>,
+) {
+    for (part, expected) in Part::BOTH.into_iter().zip([part_1.into(), part_2.into()]) {
+        let Some(expected) = expected else {
+            continue;
+        };
+        let answer = DAYS[day - 1]
+            .solve(part, input)
+            .unwrap_or_else(|e| e.to_string());
+        assert_eq!(answer, expected, "day {day} part {part}");
+    }
+}
+
+/// Registers a test per case for one day: `name: input => part 1 answer, part 2 answer;`
+macro_rules! examples {
+    ($day:literal { $($name:ident: $input:expr => $part_1:expr, $part_2:expr;)* }) => {
+        $(
+            #[test]
+            fn $name() {
+                super::check($day, $input, $part_1, $part_2);
+            }
+        )*
+    };
+}
+
+mod day01 {
+    use aoc2025::day01::Dial;
+    use std::num::NonZeroU64;
+
+    const EXAMPLE: &str = "\
+L68
+L30
+R48
+L5
+R60
+L55
+L1
+L99
+R14
+L82
+";
+
+    const SIGNED_CLICKS: &str = "invalid input at line 1, column 2: expected a digit, found '-'";
+    const PLUS_CLICKS: &str = "invalid input at line 1, column 2: expected a digit, found '+'";
+
+    examples!(1 {
+        example: EXAMPLE => "3", "6";
+        rotation_of_zero: "R0\n" => "0", "0";
+        rotation_of_zero_on_zero: "L50\nR0\n" => "2", "1";
+        rotation_of_hundred: "R50\nL100\n" => "2", "2";
+        many_full_turns: "R1000\n" => "0", "10";
+        left_turns_from_zero: "L50\nL100\nL1\nL99\n" => "3", "3";
+        negative_clicks: "L-5\n" => SIGNED_CLICKS, SIGNED_CLICKS;
+        plus_sign: "R+5\n" => PLUS_CLICKS, PLUS_CLICKS;
+        huge_rotations: "R9000000000000000050\nL9000000000000000000\n" => "2", "180000000000000001";
+    });
+
+    #[test]
+    fn dial_with_two_marks() {
+        let dial = Dial::new(NonZeroU64::new(10).unwrap(), 12, [3, 7, 13]);
+        let rotations = [10, -5, 4, -23];
+        assert_eq!(dial.trace(rotations), [2, 2, 7, 1, 8]);
+        assert_eq!(dial.count_stops(rotations), 1);
+        assert_eq!(dial.count_passes(rotations).unwrap(), 7);
+        assert_eq!(
+            (dial.size().get(), dial.start(), dial.marks()),
+            (10, 2, &[3, 7][..])
+        );
+    }
+
+    #[test]
+    fn passes_overflow() {
+        let dial = Dial::new(NonZeroU64::MIN, 0, [0]);
+        let err = dial.count_passes([i64::MAX; 3]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unsolvable input: count of passes overflows u64"
+        );
+    }
+}
+
+mod day02 {
+    use aoc2025::day02::{count_invalid, is_invalid};
+
+    const EXAMPLE: &str = "\
+11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,\
+38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124
+";
+
+    examples!(2 {
+        example: EXAMPLE => "1227775554", "4174379265";
+        single_digits: "1-9\n" => "0", "0";
+        single_id: "11-11\n" => "11", "11";
+        whole_u64_space: "1-18446744073709551615\n"
+            => "12509613850169742155792778978", "12510120345498176585131248687";
+        sum_overflows: "1-340282366920938463463374607431768211455\n"
+            => "unsolvable input: sum of invalid IDs overflows u128", None;
+    });
+
+    #[test]
+    fn single_ids() {
+        assert_eq!(is_invalid(1111, 2), Some((11, 2)));
+        assert_eq!(is_invalid(1111, u32::MAX), Some((1, 4)));
+        assert_eq!(is_invalid(123_123_123, 2), None);
+        assert_eq!(is_invalid(123_123_123, 3), Some((123, 3)));
+        assert_eq!(is_invalid(7, u32::MAX), None);
+    }
+
+    #[test]
+    fn counts() {
+        assert_eq!(count_invalid(95, 115, 2), 1);
+        assert_eq!(count_invalid(95, 115, u32::MAX), 2);
+        assert_eq!(count_invalid(1, u64::MAX.into(), 2), 1_844_674_407);
+    }
+}
+
+mod day03 {
+    use aoc2025::day03::{parse_line, solve, solve_bank};
+
+    const EXAMPLE: &str = "\
+987654321111111
+811111111111119
+234234234234278
+818181911112111
+";
+
+    examples!(3 {
+        example: EXAMPLE => "357", "3121910778619";
+        bank_too_short: "12\n" => "12", "unsolvable input: bank on line 1 has 2 batteries, need 12";
+    });
+
+    #[test]
+    fn chosen_batteries() {
+        let bank = parse_line("818181911112111").unwrap();
+        let selection = solve_bank(&bank, 12);
+        assert_eq!(selection.joltage, 888_911_112_111u64.into());
+        assert_eq!(selection.indices, [0, 2, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+        assert_eq!(solve_bank(&bank, 2).indices, [6, 11]);
+    }
+
+    #[test]
+    fn more_digits_than_u64() {
+        let banks = ["1234567890123456789012345", "9876543210987654321098765"]
+            .map(|line| parse_line(line).unwrap());
+        let total = solve(&banks, 24).unwrap();
+        assert_eq!(total.to_string(), "1222222212111111110111110");
+    }
+}
+
+mod day04 {
+    use aoc::Point;
+    use aoc2025::day04::{Tile, ascii_frames, removal_waves, write_ppm_frame};
+    use aoc2025::erosion::{ErosionRule, Neighbourhood};
+    use aoc2025::error::parse_grid;
+
+    const EXAMPLE: &str = "\
+..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@.@@
+@.@@@@..@.
+@@.@@@@.@@
+.@@@@@@@.@
+.@.@.@.@@@
+@.@@@.@@@@
+.@@@@@@@@.
+@.@.@@@.@.
+";
+
+    const EMPTY_GRID: &str = "invalid input at line 1, column 1: empty grid";
+
+    examples!(4 {
+        example: EXAMPLE => "13", "43";
+        single_roll: "@\n" => "1", "1";
+        full_block: "@@@\n@@@\n@@@\n" => "4", "9";
+        ring: "@@@\n@.@\n@@@\n" => "4", "8";
+        empty: "" => EMPTY_GRID, EMPTY_GRID;
+    });
+
+    #[test]
+    fn ring_waves() {
+        const RING: &str = "@@@\n@.@\n@@@\n";
+        let grid = parse_grid(RING, Tile::parse).unwrap();
+        let waves = removal_waves(&mut parse_grid(RING, Tile::parse).unwrap());
+        let remaining: Vec<usize> = waves.iter().map(|w| w.remaining).collect();
+        assert_eq!(remaining, [4, 0]);
+        assert_eq!(
+            waves[0].removed,
+            [Point(0, 0), Point(2, 0), Point(0, 2), Point(2, 2)]
+        );
+        assert_eq!(
+            ascii_frames(&grid, &waves),
+            ["@@@\n@.@\n@@@\n", "x@x\n@.@\nx@x\n", ".x.\nx.x\n.x.\n"]
+        );
+
+        let mut ppm = vec![];
+        write_ppm_frame(&grid, &waves, 1, 2, &mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n6 6\n255\n"));
+        assert_eq!(ppm.len(), 11 + 6 * 6 * 3);
+    }
+
+    #[test]
+    fn other_rules() {
+        let solid = |t: &Tile| matches!(t, Tile::Roll);
+        let remaining = |rule: ErosionRule<Tile>, input: &str| -> Vec<usize> {
+            let mut grid = parse_grid(input, Tile::parse).unwrap();
+            let waves = rule.erode(&mut grid, &Tile::Empty);
+            waves.iter().map(|w| w.remaining).collect()
+        };
+
+        let von_neumann = ErosionRule {
+            neighbourhood: Neighbourhood::VonNeumann,
+            threshold: 3,
+            solid,
+        };
+        assert_eq!(remaining(von_neumann, "@@@\n@@@\n@@@\n"), [5, 1, 0]);
+
+        let right_only = ErosionRule {
+            neighbourhood: Neighbourhood::Custom(vec![(1, 0)]),
+            threshold: 1,
+            solid,
+        };
+        assert_eq!(remaining(right_only, "@@@\n"), [2, 1, 0]);
+    }
+}
+
+mod day05 {
+    use aoc2025::day05::{freshness_report, parse_input};
+    use aoc2025::range_set::RangeSet;
+
+    const EXAMPLE: &str = "\
+3-5
+10-14
+16-20
+12-18
+
+1
+5
+8
+11
+17
+32
+";
+
+    const AT_U64_MAX: &str = "\
+5-18446744073709551615
+0-4
+18446744073709551615-18446744073709551615
+
+18446744073709551615
+";
+
+    examples!(5 {
+        example: EXAMPLE => "3", "14";
+        touching_ranges: "1-2\n3-4\n\n3\n" => "1", "4";
+        ranges_at_u64_max: AT_U64_MAX => "1", "18446744073709551616";
+    });
+
+    #[test]
+    fn example_report() {
+        let (ranges, ingredients) = parse_input(EXAMPLE).unwrap();
+        let report = freshness_report(&ranges, &ingredients);
+        let covering: Vec<(u64, &[usize])> = report
+            .ingredients
+            .iter()
+            .map(|it| (it.id, it.covering_ranges.as_slice()))
+            .collect();
+        assert_eq!(
+            covering,
+            [
+                (1, &[][..]),
+                (5, &[0]),
+                (8, &[]),
+                (11, &[1]),
+                (17, &[2, 3]),
+                (32, &[])
+            ]
+        );
+        assert!(report.unused_ranges.is_empty());
+        assert!(report.contained_ranges.is_empty());
+    }
+
+    #[test]
+    fn redundant_and_unused_ranges() {
+        let (ranges, ingredients) = parse_input("1-10\n2-3\n1-10\n20-30\n\n2\n").unwrap();
+        let report = freshness_report(&ranges, &ingredients);
+        assert_eq!(report.ingredients[0].covering_ranges, [0, 1, 2]);
+        assert!(report.ingredients[0].is_fresh());
+        assert_eq!(report.unused_ranges, [3]);
+        assert_eq!(report.contained_ranges, [(1, 0), (1, 2), (2, 0)]);
+    }
+
+    #[test]
+    fn range_set_operations() {
+        let a: RangeSet<u64> = [(10, 14), (3, 5), (12, 18), (19, 20)].into_iter().collect();
+        let b: RangeSet<u64> = [(1, 3), (8, 11), (15, 16)].into_iter().collect();
+        assert_eq!(a.ranges(), [(3, 5), (10, 20)]);
+        assert_eq!(a.len(), 14);
+        assert!(a.contains(20) && !a.contains(21) && !a.contains(9));
+        assert_eq!(
+            [2, 4, 15, 21].map(|n| a.position(n)),
+            [None, Some(0), Some(1), None]
+        );
+        assert_eq!(a.union(&b).ranges(), [(1, 5), (8, 20)]);
+        assert_eq!(a.intersection(&b).ranges(), [(3, 3), (10, 11), (15, 16)]);
+        assert_eq!(a.difference(&b).ranges(), [(4, 5), (12, 14), (17, 20)]);
+    }
+
+    #[test]
+    fn range_set_at_u64_max() {
+        let top: RangeSet<u64> = [(u64::MAX - 2, u64::MAX)].into_iter().collect();
+        let cut: RangeSet<u64> = [(u64::MAX - 1, u64::MAX - 1)].into_iter().collect();
+        assert_eq!(
+            top.difference(&cut).ranges(),
+            [(u64::MAX - 2, u64::MAX - 2), (u64::MAX, u64::MAX)]
+        );
+        assert_eq!(top.union(&cut).len(), 3);
+        assert_eq!(cut.difference(&top).len(), 0);
+    }
+}
+
+mod day06 {
+    use aoc2025::day06::{breakdown, read_columns};
+    use aoc2025::expression::Op;
+
+    const EXAMPLE: &str = concat!(
+        "123 328  51 64 \n",
+        " 45 64  387 23 \n",
+        "  6 98  215 314\n",
+        "*   +   *   +  \n",
+    );
+
+    const EXAMPLE_STRIPPED: &str = "\
+123 328  51 64
+ 45 64  387 23
+  6 98  215 314
+*   +   *   +
+";
+
+    examples!(6 {
+        example: EXAMPLE => "4277556", "3263827";
+        trailing_spaces_stripped: EXAMPLE_STRIPPED => "4277556", "3263827";
+        more_operators: "10 3 5 12 7\n 4 2 9 34 1\n-  / max || min\n" => "1251", None;
+        division_by_zero: "1\n0\n/\n"
+            => "unsolvable input: problem 1 (1 / 0): division by zero", None;
+        below_zero: "1\n2\n-\n"
+            => "unsolvable input: problem 1 (1 - 2): subtraction goes below zero", None;
+        overflow: "340282366920938463463374607431768211455\n2\n*\n"
+            => concat!(
+                "unsolvable input: problem 1 (340282366920938463463374607431768211455 * 2): ",
+                "'*' overflows u128"
+            ), None;
+        number_before_first_operator: "1  2\n3  4\n +  *\n"
+            => "12", "invalid input at line 1, column 1: number comes before the first operator";
+        operator_without_numbers: "1 2\n3 4\n+ + +\n"
+            => "invalid input at line 1, column 4: expected 3 numbers, found 2",
+               "invalid input at line 3, column 5: no numbers under operator '+'";
+    });
+
+    #[test]
+    fn example_breakdown() {
+        let answers = breakdown(&read_columns(EXAMPLE).unwrap()).unwrap();
+        let values: Vec<u128> = answers.iter().map(|a| a.value).collect();
+        assert_eq!(values, [8544, 625, 3253600, 1058]);
+        assert_eq!(answers[0].problem.to_string(), "356 * 24 * 1");
+        assert_eq!(answers[3].problem.op, Op::Add);
+    }
+}
+
+mod day07 {
+    use aoc2025::day07::{Counting, EdgePolicy, Tile, count_splits, timeline_map, timelines};
+    use aoc2025::error::parse_grid;
+    use num_bigint::BigUint;
+    use std::num::NonZeroU64;
+
+    const EXAMPLE: &str = "\
+.......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+...............
+";
+
+    const EDGE_SPLITTERS: &str = "\
+.S.
+...
+.^.
+...
+^.^
+";
+
+    const MIRRORS: &str = "\
+..S..
+..>.^
+...#.
+";
+
+    examples!(7 {
+        example: EXAMPLE => "21", "40";
+        edge_splitters: EDGE_SPLITTERS => "3", "4";
+        splitter_on_narrow_manifold: "S\n^\n" => "1", "2";
+        mirror_into_splitter: MIRRORS => "1", "2";
+        odd_fan: "..S..\n..5..\n" => "1", "5";
+        even_fan: "..S..\n..4..\n" => "1", "4";
+    });
+
+    #[test]
+    fn deep_manifold() {
+        // every beam splits on every row, so the count doubles each time
+        let map = parse_grid(&format!("S..\n{}", "^^^\n".repeat(300)), Tile::parse).unwrap();
+        let exact = timelines(&map, EdgePolicy::Wrap, Counting::Exact).unwrap();
+        assert_eq!(exact.total(), BigUint::from(1u8) << 300);
+
+        let modulus = NonZeroU64::new(1_000_000_007).unwrap();
+        let reduced = timelines(&map, EdgePolicy::Wrap, Counting::Modulo(modulus)).unwrap();
+        assert_eq!(reduced.total(), exact.total() % modulus.get());
+    }
+
+    #[test]
+    fn example_timeline_map() {
+        let map = parse_grid(EXAMPLE, Tile::parse).unwrap();
+        let timeline_map = timeline_map(&map, EdgePolicy::default(), Counting::Exact).unwrap();
+        assert_eq!(timeline_map.splitters_hit.len(), 21);
+        assert_eq!(timeline_map.timelines.total(), BigUint::from(40u8));
+
+        let ascii = timeline_map.render_ascii(&map);
+        assert_eq!(ascii.lines().next(), Some(".......S......."));
+        assert_eq!(ascii.lines().nth(2), Some(".......^......."));
+        assert_eq!(ascii.matches('-').count(), 1);
+
+        let mut pgm = vec![];
+        timeline_map.write_heatmap(&map, 1, &mut pgm).unwrap();
+        let header = b"P5\n15 16\n255\n";
+        assert!(pgm.starts_with(header));
+        assert_eq!(pgm.len(), header.len() + 15 * 16);
+        assert!(pgm[header.len()..].contains(&255));
+    }
+
+    #[test]
+    fn edge_splitter_paths() {
+        let map = parse_grid(EDGE_SPLITTERS, Tile::parse).unwrap();
+        let timeline_map = timeline_map(&map, EdgePolicy::default(), Counting::Exact).unwrap();
+        assert_eq!(timeline_map.render_ascii(&map), ".S.\n.|.\n.^.\n|.|\n^.^\n");
+    }
+
+    #[test]
+    fn edge_policies() {
+        let map = parse_grid(EDGE_SPLITTERS, Tile::parse).unwrap();
+        for (policy, total, bottom) in [
+            (EdgePolicy::Drop, 2u32, [0u32, 2, 0]),
+            (EdgePolicy::CountEscaped, 4, [0, 2, 0]),
+            (EdgePolicy::Wrap, 4, [1, 2, 1]),
+        ] {
+            assert_eq!(count_splits(&map, policy).unwrap(), 3, "{policy:?}");
+            let timelines = timelines(&map, policy, Counting::Exact).unwrap();
+            assert_eq!(timelines.total(), BigUint::from(total), "{policy:?}");
+            let columns = [0, 1, 2].map(|x| {
+                timelines
+                    .columns
+                    .get(&x)
+                    .map_or(0, |n| u32::try_from(n).unwrap())
+            });
+            assert_eq!(columns, bottom, "{policy:?}");
+        }
+    }
+
+    #[test]
+    fn mirrors_and_absorbers() {
+        let map = parse_grid(MIRRORS, Tile::parse).unwrap();
+        let timeline_map = timeline_map(&map, EdgePolicy::default(), Counting::Exact).unwrap();
+        assert_eq!(timeline_map.render_ascii(&map), "..S..\n..>|^\n...#.\n");
+        let timelines = timeline_map.timelines;
+        assert!(timelines.columns.is_empty());
+        assert_eq!(timelines.absorbed, BigUint::from(1u8));
+        assert_eq!(timelines.escaped, BigUint::from(1u8));
+    }
+
+    #[test]
+    fn unreached_fan() {
+        let map = parse_grid("S.\n.5\n", Tile::parse).unwrap();
+        let timeline_map = timeline_map(&map, EdgePolicy::default(), Counting::Exact).unwrap();
+        assert_eq!(timeline_map.render_ascii(&map), "S.\n|-\n");
+    }
+
+    #[test]
+    fn looping_beam() {
+        let map = parse_grid("S..\n>.<\n", Tile::parse).unwrap();
+        assert_eq!(count_splits(&map, EdgePolicy::default()).unwrap(), 0);
+        let Err(err) = timelines(&map, EdgePolicy::default(), Counting::Exact) else {
+            panic!("a looping beam has no end of timelines");
+        };
+        assert_eq!(
+            err.to_string(),
+            "unsolvable input: beam loops forever through (1, 1)"
+        );
+    }
+}
+
+mod day08 {
+    use aoc2025::day08::Solver;
+    use aoc2025::disjoint_set::DisjointSets;
+
+    const EXAMPLE: &str = "\
+162,817,812
+57,618,57
+906,360,560
+592,479,940
+352,342,300
+466,668,158
+542,29,236
+431,825,988
+739,650,466
+52,470,668
+216,146,977
+819,987,18
+117,168,530
+805,96,715
+346,949,466
+970,615,88
+941,993,340
+862,61,35
+984,92,344
+425,690,689
+";
+
+    // the example only makes 10 connections in part 1, rather than 1000
+    examples!(8 {
+        example: EXAMPLE => None, "25272";
+    });
+
+    #[test]
+    fn example_ten_connections() {
+        let mut sizes: Vec<usize> = Solver::new(EXAMPLE)
+            .unwrap()
+            .solve_part_1(10)
+            .iter()
+            .map(|c| c.len())
+            .collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(sizes[0] * sizes[1] * sizes[2], 40);
+    }
+
+    #[test]
+    fn disjoint_sets() {
+        let mut sets = DisjointSets::new(6);
+        assert_eq!(sets.components(), 6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert_eq!(sets.components(), 3);
+        assert_eq!(sets.size_of(2), 4);
+        assert_eq!(sets.find(0), sets.find(3));
+        assert_ne!(sets.find(0), sets.find(4));
+        assert_eq!(sets.size_of(5), 1);
+    }
+}
+
+mod day09 {
+    const EXAMPLE: &str = "\
+7,1
+11,1
+11,7
+9,7
+9,5
+2,5
+2,3
+7,3
+";
+
+    examples!(9 {
+        example: EXAMPLE => "50", "24";
+    });
+}
+
+mod day10 {
+    const EXAMPLE: &str = "\
+[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}
+";
+
+    examples!(10 {
+        example: EXAMPLE => "7", "33";
+        first_machine: EXAMPLE.lines().next().unwrap() => "2", "10";
+    });
+}
+
+mod day11 {
+    const EXAMPLE_1: &str = "\
+aaa: you hhh
+you: bbb ccc
+bbb: ddd eee
+ccc: ddd eee fff
+ddd: ggg
+eee: out
+fff: out
+ggg: out
+hhh: ccc fff iii
+iii: out
+";
+
+    const EXAMPLE_2: &str = "\
+svr: aaa bbb
+aaa: fft
+fft: ccc
+bbb: tty
+tty: ccc
+ccc: ddd eee
+ddd: hub
+hub: fff
+eee: dac
+dac: fff
+fff: ggg hhh
+ggg: out
+hhh: out
+";
+
+    examples!(11 {
+        example_1: EXAMPLE_1 => "5", None;
+        example_2: EXAMPLE_2 => None, "2";
+    });
+}
+
+mod day12 {
+    const EXAMPLE: &str = "\
+0:
+###
+##.
+##.
+
+1:
+###
+##.
+.##
+
+2:
+.##
+###
+##.
+
+3:
+##.
+###
+##.
+
+4:
+###
+#..
+###
+
+5:
+###
+.#.
+###
+
+4x4: 0 0 0 0 2 0
+12x5: 1 0 1 0 2 2
+12x5: 1 0 1 0 3 2
+";
+
+    const BLOCK: &str = "0:\n###\n###\n###\n\n";
+
+    examples!(12 {
+        fits_without_packing: &format!("{BLOCK}6x3: 2\n") => "1", None;
+        too_small_for_cells: &format!("{BLOCK}2x2: 1\n") => "0", None;
+    });
+
+    // the example's regions are all tight enough to need real packing, which isn't done yet
+    #[test]
+    #[ignore = "day 12 packing is not implemented"]
+    fn example() {
+        super::check(12, EXAMPLE, "2", None);
+    }
+}